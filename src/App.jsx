@@ -7,12 +7,26 @@ import {
   parseCertificateFile, 
   extractCertificateInfo, 
   buildCertificateChain,
-  generateNginxFormat 
+  generateNginxFormat,
+  certToDer,
 } from './utils/certificateParser';
+import { CertificateErrorCode } from './utils/errors';
+
+function downloadBlob(blob, fileName) {
+  const url = URL.createObjectURL(blob);
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = fileName;
+  document.body.appendChild(a);
+  a.click();
+  document.body.removeChild(a);
+  URL.revokeObjectURL(url);
+}
 
 function App() {
   const [certificates, setCertificates] = useState([]);
   const [privateKeys, setPrivateKeys] = useState([]);
+  const [otherItems, setOtherItems] = useState([]);
   const [pendingFile, setPendingFile] = useState(null);
   const [showPasswordModal, setShowPasswordModal] = useState(false);
   const [error, setError] = useState(null);
@@ -33,13 +47,22 @@ function App() {
         ...cert,
         info: extractCertificateInfo(cert.data),
         fileName: file.name,
+        pkcs12WeakEncryption: result.pkcs12WeakEncryption === true,
       }));
 
-      setCertificates(prev => [...prev, ...certsWithInfo]);
+      // Skip certificates already loaded from another file (same DER fingerprint)
+      setCertificates(prev => {
+        const loaded = new Set(prev.map(cert => cert.info.sha256Fingerprint));
+        return [...prev, ...certsWithInfo.filter(cert => !loaded.has(cert.info.sha256Fingerprint))];
+      });
       setPrivateKeys(prev => [...prev, ...result.privateKeys.map(key => ({
         ...key,
         fileName: file.name,
       }))]);
+      setOtherItems(prev => [...prev, ...(result.others || []).map(item => ({
+        ...item,
+        fileName: file.name,
+      }))]);
 
       console.log(`Processed ${file.name}:`, {
         certificates: certsWithInfo.length,
@@ -47,6 +70,10 @@ function App() {
       });
     } catch (err) {
       console.error('Error processing file:', err);
+      if (err.code === CertificateErrorCode.EMPTY_INPUT) {
+        setError(`${file.name} is empty: it contains no certificate, key or bundle data`);
+        return;
+      }
       setError(`Failed to process ${file.name}: ${err.message}`);
     }
   };
@@ -87,15 +114,7 @@ function App() {
       const nginxFormat = generateNginxFormat(chain, privateKey);
 
       // Download the file
-      const blob = new Blob([nginxFormat], { type: 'text/plain' });
-      const url = URL.createObjectURL(blob);
-      const a = document.createElement('a');
-      a.href = url;
-      a.download = 'nginx-certificate-chain.pem';
-      document.body.appendChild(a);
-      a.click();
-      document.body.removeChild(a);
-      URL.revokeObjectURL(url);
+      downloadBlob(new Blob([nginxFormat], { type: 'text/plain' }), 'nginx-certificate-chain.pem');
 
       console.log('Downloaded nginx certificate chain');
     } catch (err) {
@@ -104,9 +123,20 @@ function App() {
     }
   };
 
+  const handleDownloadDer = (cert) => {
+    try {
+      const fileName = `${cert.info.displayName.replace(/[^A-Za-z0-9.-]+/g, '_')}.der`;
+      downloadBlob(new Blob([certToDer(cert.pem)], { type: 'application/pkix-cert' }), fileName);
+    } catch (err) {
+      console.error('Error exporting DER:', err);
+      setError(`Failed to export DER: ${err.message}`);
+    }
+  };
+
   const handleClearAll = () => {
     setCertificates([]);
     setPrivateKeys([]);
+    setOtherItems([]);
     setError(null);
   };
 
@@ -128,11 +158,14 @@ function App() {
           </div>
         )}
 
-        {(certificates.length > 0 || privateKeys.length > 0) && (
+        {(certificates.length > 0 || privateKeys.length > 0 || otherItems.length > 0) && (
           <div className="summary-bar">
             <div className="summary-info">
               <span>📄 {certificates.length} Certificate(s)</span>
               <span>🔑 {privateKeys.length} Private Key(s)</span>
+              {otherItems.length > 0 && (
+                <span>📎 {otherItems.length} Other ({[...new Set(otherItems.map(item => item.pemType))].join(', ')})</span>
+              )}
             </div>
             <button onClick={handleClearAll} className="clear-button">
               Clear All
@@ -152,16 +185,34 @@ function App() {
             {certificates.map((cert, index) => (
               <div key={index} className="cert-detail-card">
                 <div className="cert-detail-header">
-                  <strong>{cert.info.subjectCommonName}</strong>
+                  <strong>{cert.info.displayName}</strong>
                   <span className="cert-tag">
                     {cert.info.isSelfSigned ? 'Root' : cert.info.isCA ? 'Intermediate' : 'End Entity'}
                   </span>
+                  <button onClick={() => handleDownloadDer(cert)} className="der-button">
+                    DER
+                  </button>
                 </div>
                 <div className="cert-detail-info">
                   <div><strong>File:</strong> {cert.fileName}</div>
                   <div><strong>Issuer:</strong> {cert.info.issuerCommonName}</div>
                   <div><strong>Valid:</strong> {new Date(cert.info.validFrom).toLocaleDateString()} - {new Date(cert.info.validTo).toLocaleDateString()}</div>
                   <div><strong>Serial:</strong> <code>{cert.info.serialNumber}</code></div>
+                  {cert.info.subjectInfoAccess.map(entry => (
+                    <div key={`${entry.oid}-${entry.uri}`}><strong>{entry.method}:</strong> {entry.uri}</div>
+                  ))}
+                  {cert.info.usesCnForHostname && (
+                    <div>⚠️ No subjectAltName: browsers ignore the CN and will reject this certificate</div>
+                  )}
+                  {cert.info.overlyBroadWildcard && (
+                    <div>⚠️ Wildcard covers a public suffix (e.g. *.com): CAs may not issue it and clients reject it</div>
+                  )}
+                  {cert.info.suspiciousName && (
+                    <div>⚠️ Subject or issuer contains control or text-direction characters (shown escaped): the name may be spoofed</div>
+                  )}
+                  {cert.pkcs12WeakEncryption && (
+                    <div>⚠️ {cert.fileName} uses weak PKCS#12 encryption (e.g. 40-bit RC2): re-export it with AES</div>
+                  )}
                 </div>
               </div>
             ))}
@@ -23,7 +23,9 @@ function CertificateNode({ data }) {
       <div className="cert-info">
         <div className="cert-field">
           <strong>Subject:</strong>
-          <div className="cert-value">{info.subjectCommonName}</div>
+          <div className="cert-value">
+            {info.displayName}
+          </div>
         </div>
         
         <div className="cert-field">
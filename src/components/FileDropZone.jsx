@@ -55,14 +55,14 @@ function FileDropZone({ onFilesDropped }) {
         <div className="drop-zone-icon">📁</div>
         <h2>Drop Certificate Files Here</h2>
         <p>
-          Supports: PEM (.pem, .crt, .cer), DER (.der), PKCS#12 (.pfx, .p12), and private keys
+          Supports: PEM (.pem, .crt, .cer), DER (.der), PKCS#12 (.pfx, .p12), JSON arrays (.json), and private keys
         </p>
         <p className="or-text">or</p>
         <label className="file-input-label">
           <input
             type="file"
             multiple
-            accept=".pem,.crt,.cer,.der,.pfx,.p12,.key"
+            accept=".pem,.crt,.cer,.der,.pfx,.p12,.key,.json"
             onChange={handleFileInput}
             className="file-input"
           />
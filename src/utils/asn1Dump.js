@@ -0,0 +1,198 @@
+import forge from 'node-forge';
+import { checkInputSize, checkNotEmpty, toBytes } from './certificateParser.js';
+
+/**
+ * Raw ASN.1 (DER) structure dumps for debugging certificates that fail to
+ * parse. Nothing here knows about X.509: it only walks tags and lengths.
+ */
+
+const UNIVERSAL_TYPES = {
+  1: 'BOOLEAN',
+  2: 'INTEGER',
+  3: 'BIT STRING',
+  4: 'OCTET STRING',
+  5: 'NULL',
+  6: 'OBJECT IDENTIFIER',
+  10: 'ENUMERATED',
+  12: 'UTF8String',
+  16: 'SEQUENCE',
+  17: 'SET',
+  18: 'NumericString',
+  19: 'PrintableString',
+  20: 'T61String',
+  22: 'IA5String',
+  23: 'UTCTime',
+  24: 'GeneralizedTime',
+  26: 'VisibleString',
+  30: 'BMPString',
+};
+
+const TAG_CLASSES = ['universal', 'application', 'context', 'private'];
+
+// Universal string and time types decoded as text (BMPString is UTF-16BE)
+const TEXT_TYPES = new Set([12, 18, 19, 20, 22, 23, 24, 26]);
+
+function _hex(data, start, end) {
+  return Array.from(data.subarray(start, end), byte => byte.toString(16).padStart(2, '0')).join('');
+}
+
+function _decodeOid(content) {
+  const arcs = [];
+  let value = 0;
+  for (const byte of content) {
+    value = value * 128 + (byte & 0x7f);
+    if (!(byte & 0x80)) {
+      arcs.push(value);
+      value = 0;
+    }
+  }
+  if (arcs.length === 0) {
+    return '';
+  }
+  const first = arcs[0] < 80 ? Math.floor(arcs[0] / 40) : 2;
+  return [first, arcs[0] - first * 40, ...arcs.slice(1)].join('.');
+}
+
+/**
+ * Decoded form of a primitive universal value, or undefined when there is none
+ */
+function _decodeValue(tagNumber, content) {
+  if (tagNumber === 1) {
+    return content.length === 1 ? content[0] !== 0 : undefined;
+  }
+  if (tagNumber === 5) {
+    return null;
+  }
+  if (tagNumber === 6) {
+    return _decodeOid(content);
+  }
+  if (tagNumber === 30) {
+    let text = '';
+    for (let i = 0; i + 1 < content.length; i += 2) {
+      text += String.fromCharCode((content[i] << 8) | content[i + 1]);
+    }
+    return text;
+  }
+  if (TEXT_TYPES.has(tagNumber)) {
+    const binary = String.fromCharCode(...content);
+    try {
+      return tagNumber === 12 ? forge.util.decodeUtf8(binary) : binary;
+    } catch {
+      return binary;
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Dump the TLV at `offset` (and, for constructed values, everything inside it).
+ * Returns { node, end }; a node with an `error` stops the walk at that byte.
+ */
+function _dumpNode(data, offset, limit) {
+  const node = { offset };
+  if (offset + 2 > limit) {
+    node.error = `Truncated header at byte ${offset}`;
+    return { node, end: limit };
+  }
+
+  const identifier = data[offset];
+  let position = offset + 1;
+  let tagNumber = identifier & 0x1f;
+  if (tagNumber === 0x1f) {
+    tagNumber = 0;
+    do {
+      if (position >= limit) {
+        node.error = `Truncated tag at byte ${position}`;
+        return { node, end: limit };
+      }
+      tagNumber = tagNumber * 128 + (data[position] & 0x7f);
+    } while (data[position++] & 0x80);
+  }
+
+  node.tagClass = TAG_CLASSES[identifier >> 6];
+  node.tagNumber = tagNumber;
+  node.constructed = (identifier & 0x20) !== 0;
+  node.type = node.tagClass === 'universal'
+    ? UNIVERSAL_TYPES[tagNumber] || `UNIVERSAL ${tagNumber}`
+    : node.tagClass === 'context' ? `[${tagNumber}]` : `${node.tagClass.toUpperCase()} ${tagNumber}`;
+
+  if (position >= limit) {
+    node.error = `Truncated length at byte ${position}`;
+    return { node, end: limit };
+  }
+  let length = data[position++];
+  if (length === 0x80) {
+    node.error = `Indefinite length at byte ${position - 1} is not allowed in DER`;
+    return { node, end: limit };
+  }
+  if (length & 0x80) {
+    const lengthBytes = length & 0x7f;
+    if (position + lengthBytes > limit) {
+      node.error = `Truncated length at byte ${position}`;
+      return { node, end: limit };
+    }
+    length = 0;
+    for (let i = 0; i < lengthBytes; i++) {
+      length = length * 256 + data[position++];
+    }
+  }
+
+  node.headerLength = position - offset;
+  node.length = length;
+  const end = position + length;
+  if (end > limit) {
+    node.error = `Length ${length} at byte ${offset} exceeds the ${limit - position} bytes available`;
+    // Walk what is there, so the innermost broken value shows up too
+    if (node.constructed) {
+      node.children = _dumpNodes(data, position, limit);
+    }
+    return { node, end: limit };
+  }
+
+  if (node.constructed) {
+    node.children = _dumpNodes(data, position, end);
+  } else {
+    const content = data.subarray(position, end);
+    node.hex = _hex(data, position, end);
+    const value = node.tagClass === 'universal' ? _decodeValue(tagNumber, content) : undefined;
+    if (value !== undefined) {
+      node.value = value;
+    }
+    if (node.tagClass === 'universal' && tagNumber === 3 && content.length > 0) {
+      node.unusedBits = content[0];
+    }
+  }
+  return { node, end };
+}
+
+function _dumpNodes(data, offset, limit) {
+  const nodes = [];
+  while (offset < limit) {
+    const { node, end } = _dumpNode(data, offset, limit);
+    nodes.push(node);
+    if (node.error) {
+      break;
+    }
+    offset = end;
+  }
+  return nodes;
+}
+
+/**
+ * Dump DER bytes (ArrayBuffer / Uint8Array, a binary string, or PEM text) as a
+ * tree of { offset, headerLength, length, tagClass, tagNumber, constructed,
+ * type, children | hex [, value] }. A malformed or truncated value gets an
+ * `error` naming the byte offset, and the walk stops there.
+ * Returns the list of top-level nodes (normally just one).
+ */
+export function dumpAsn1(bytes, options = {}) {
+  checkInputSize(bytes, options.maxInputBytes);
+  checkNotEmpty(bytes);
+  let binary = typeof bytes === 'string' ? bytes : forge.util.createBuffer(bytes).getBytes();
+  const pem = binary.match(/-----BEGIN ([A-Z0-9 ]+)-----([\s\S]*?)-----END \1-----/);
+  if (pem) {
+    binary = forge.util.decode64(pem[2].replace(/\s/g, ''));
+  }
+  const data = toBytes(binary);
+  return _dumpNodes(data, 0, data.length);
+}
@@ -0,0 +1,682 @@
+import forge from 'node-forge';
+import {
+  EC_CURVES,
+  KEY_USAGE_BITS,
+  MIN_RSA_KEY_BITS,
+  certificateFromPem,
+  checkInputSize,
+  checkNotEmpty,
+  extractCertificateInfo,
+  fromDerRaw,
+  publicKeyDetails,
+  tbsCertificateFields,
+  toBytes,
+  toPem,
+} from './certificateParser.js';
+import { verifySignature } from './signatures.js';
+import { REVOCATION_REASONS } from './revocation.js';
+
+/**
+ * Certificate generation: self-signed certificates, signing CSRs, building CRLs
+ * and ACME keys.
+ * Keys are generated and used through WebCrypto.
+ */
+
+const asn1 = forge.asn1;
+
+// Name attributes in the order they are written (least specific first)
+const SUBJECT_ATTRIBUTES = [
+  ['C', '2.5.4.6'],
+  ['ST', '2.5.4.8'],
+  ['L', '2.5.4.7'],
+  ['O', '2.5.4.10'],
+  ['OU', '2.5.4.11'],
+  ['CN', '2.5.4.3'],
+];
+
+// RFC 5280 4.2.1.12 key purpose names accepted for extendedKeyUsage
+const EXTENDED_KEY_USAGES = {
+  serverAuth: '1.3.6.1.5.5.7.3.1',
+  clientAuth: '1.3.6.1.5.5.7.3.2',
+  codeSigning: '1.3.6.1.5.5.7.3.3',
+  emailProtection: '1.3.6.1.5.5.7.3.4',
+  timeStamping: '1.3.6.1.5.5.7.3.8',
+  OCSPSigning: '1.3.6.1.5.5.7.3.9',
+};
+
+// Named certificate profiles; any of these fields may be overridden in the options
+const PROFILES = {
+  'tls-server': { isCA: false, keyUsage: ['digitalSignature'], extendedKeyUsage: ['serverAuth'] },
+  'tls-client': { isCA: false, keyUsage: ['digitalSignature'], extendedKeyUsage: ['clientAuth'] },
+  'ca-root': { isCA: true, keyUsage: ['keyCertSign', 'cRLSign'] },
+  'ca-intermediate': { isCA: true, keyUsage: ['digitalSignature', 'keyCertSign', 'cRLSign'], pathLength: 0 },
+};
+
+// WebCrypto parameters for the key types generateAcmeKey offers
+const ACME_KEY_ALGORITHMS = {
+  'ec-p256': { name: 'ECDSA', namedCurve: 'P-256' },
+  'rsa-2048': { name: 'RSASSA-PKCS1-v1_5', modulusLength: 2048, publicExponent: new Uint8Array([1, 0, 1]), hash: 'SHA-256' },
+};
+
+// PBES2 parameters for encrypted private key output (PBKDF2-HMAC-SHA256, AES-256-CBC)
+const KEY_ENCRYPTION_ITERATIONS = 100000;
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+function _fromBytes(buffer) {
+  return forge.util.createBuffer(buffer).getBytes();
+}
+
+function _pemToDer(pem) {
+  return forge.util.decode64(pem.replace(/-----(BEGIN|END) [A-Z0-9 ]+-----/g, '').replace(/\s/g, ''));
+}
+
+function _sequence(children) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, children);
+}
+
+function _oid(oid) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OID, false, asn1.oidToDer(oid).getBytes());
+}
+
+function _integer(bytes) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.INTEGER, false, bytes);
+}
+
+function _octetString(bytes) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OCTETSTRING, false, bytes);
+}
+
+function _bitString(bytes, unusedBits = 0) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.BITSTRING, false, String.fromCharCode(unusedBits) + bytes);
+}
+
+/**
+ * Encode a positive serial number given as hex, or a random 16-byte one.
+ * RFC 5280 limits serials to 20 octets including the sign byte.
+ */
+function _serialNumber(serialHex) {
+  if (serialHex === undefined || serialHex === null) {
+    const random = globalThis.crypto.getRandomValues(new Uint8Array(16));
+    // Clear the sign bit and make sure the leading byte is not zero
+    random[0] = (random[0] & 0x7f) | 0x40;
+    return _fromBytes(random);
+  }
+
+  const hex = String(serialHex).replace(/:/g, '').toLowerCase();
+  if (!/^[0-9a-f]+$/.test(hex)) {
+    throw new Error('Serial number must be a hex string');
+  }
+  let bytes = forge.util.hexToBytes(hex.length % 2 ? '0' + hex : hex).replace(/^\x00+/, '');
+  if (!bytes) {
+    throw new Error('Serial number must be positive');
+  }
+  if (bytes.charCodeAt(0) & 0x80) {
+    bytes = '\x00' + bytes;
+  }
+  if (bytes.length > 20) {
+    throw new Error('Serial number must be at most 20 bytes');
+  }
+  return bytes;
+}
+
+/**
+ * Merge the named profile (if any) under the caller's options and check the
+ * keyUsage names and pathLength it ends up with
+ */
+function _withProfile(options) {
+  if (options.profile !== undefined && !PROFILES[options.profile]) {
+    throw new Error(`Unknown certificate profile: ${options.profile}`);
+  }
+  const overrides = Object.fromEntries(Object.entries(options).filter(([, value]) => value !== undefined));
+  const resolved = { ...PROFILES[options.profile], ...overrides };
+
+  if (resolved.keyUsage !== undefined && (!Array.isArray(resolved.keyUsage) || resolved.keyUsage.length === 0)) {
+    throw new Error('keyUsage must be a non-empty list');
+  }
+  const unknownUsage = (resolved.keyUsage || []).find(name => !KEY_USAGE_BITS.includes(name));
+  if (unknownUsage) {
+    throw new Error(`Unknown key usage: ${unknownUsage}`);
+  }
+  if (resolved.pathLength !== undefined && !(Number.isInteger(resolved.pathLength) && resolved.pathLength >= 0)) {
+    throw new Error('pathLength must be a non-negative integer');
+  }
+  return resolved;
+}
+
+/**
+ * Resolve extendedKeyUsage names (serverAuth, clientAuth, ...) or dotted OIDs
+ * to OIDs; serverAuth when not given
+ */
+function _extendedKeyUsageOids(purposes = ['serverAuth']) {
+  if (!Array.isArray(purposes) || purposes.length === 0) {
+    throw new Error('extendedKeyUsage must be a non-empty list');
+  }
+  return purposes.map(purpose => {
+    if (EXTENDED_KEY_USAGES[purpose]) {
+      return EXTENDED_KEY_USAGES[purpose];
+    }
+    if (/^[0-2](\.\d+)+$/.test(purpose)) {
+      return purpose;
+    }
+    throw new Error(`Unknown extended key usage: ${purpose}`);
+  });
+}
+
+/**
+ * Resolve the validity window from explicit notBefore/notAfter (Date or
+ * anything Date accepts) or from validityDays starting now
+ */
+function _validityPeriod(options) {
+  const notBefore = options.notBefore !== undefined ? new Date(options.notBefore) : new Date();
+  const notAfter = options.notAfter !== undefined
+    ? new Date(options.notAfter)
+    : new Date(notBefore.getTime() + (options.validityDays ?? 365) * DAY_MS);
+
+  if (isNaN(notBefore.getTime()) || isNaN(notAfter.getTime())) {
+    throw new Error('Invalid validity date');
+  }
+  if (notAfter <= notBefore) {
+    throw new Error('notAfter must be later than notBefore');
+  }
+  return { notBefore, notAfter };
+}
+
+// RFC 5280: UTCTime through 2049, GeneralizedTime from 2050
+function _time(date) {
+  const year = date.getUTCFullYear();
+  if (year >= 1950 && year < 2050) {
+    return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.UTCTIME, false, asn1.dateToUtcTime(date));
+  }
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.GENERALIZEDTIME, false, asn1.dateToGeneralizedTime(date));
+}
+
+/**
+ * Build a Name from a { CN, O, OU, C, ST, L } map (the shape of certificate info subjects)
+ */
+function _name(subject) {
+  return asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, SUBJECT_ATTRIBUTES
+    .filter(([shortName]) => subject[shortName])
+    .map(([shortName, oid]) => asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SET, true, [
+      _sequence([
+        _oid(oid),
+        shortName === 'C'
+          ? asn1.create(asn1.Class.UNIVERSAL, asn1.Type.PRINTABLESTRING, false, subject[shortName])
+          : asn1.create(asn1.Class.UNIVERSAL, asn1.Type.UTF8, false, forge.util.encodeUtf8(subject[shortName])),
+      ]),
+    ])));
+}
+
+function _extension(oid, critical, value) {
+  const fields = [_oid(oid)];
+  if (critical) {
+    fields.push(asn1.create(asn1.Class.UNIVERSAL, asn1.Type.BOOLEAN, false, '\xff'));
+  }
+  fields.push(_octetString(asn1.toDer(value).getBytes()));
+  return _sequence(fields);
+}
+
+function _keyUsage(names) {
+  const indices = names.map(name => KEY_USAGE_BITS.indexOf(name));
+  const bitCount = Math.max(...indices) + 1;
+  const bytes = new Uint8Array(Math.ceil(bitCount / 8));
+  indices.forEach(i => {
+    bytes[Math.floor(i / 8)] |= 0x80 >> (i % 8);
+  });
+  return _bitString(_fromBytes(bytes), bytes.length * 8 - bitCount);
+}
+
+function _keyIdentifier(spkiDer) {
+  // Method 1 of RFC 5280 4.2.1.2: SHA-1 of the subjectPublicKey bits
+  const keyBits = fromDerRaw(spkiDer).value[1].value.substring(1);
+  return forge.md.sha1.create().update(keyBits).digest().getBytes();
+}
+
+/**
+ * Extensions for an issued certificate: CA or server leaf profile, unless
+ * keyUsage is given
+ */
+function _extensions({ isCA, pathLength, keyUsage, dnsNames, extendedKeyUsage, subjectKeyId, authorityKeyId }) {
+  const basicConstraints = [];
+  if (isCA) {
+    basicConstraints.push(asn1.create(asn1.Class.UNIVERSAL, asn1.Type.BOOLEAN, false, '\xff'));
+    if (pathLength !== undefined) {
+      basicConstraints.push(_integer(asn1.integerToDer(pathLength).getBytes()));
+    }
+  }
+  const extensions = [
+    _extension('2.5.29.19', true, _sequence(basicConstraints)),
+    _extension('2.5.29.15', true, _keyUsage(keyUsage ||
+      (isCA ? ['digitalSignature', 'keyCertSign', 'cRLSign'] : ['digitalSignature']))),
+  ];
+  if (extendedKeyUsage) {
+    extensions.push(_extension('2.5.29.37', false, _sequence(extendedKeyUsage.map(_oid))));
+  }
+  if (dnsNames.length > 0) {
+    extensions.push(_extension('2.5.29.17', false, _sequence(dnsNames.map(dnsName =>
+      asn1.create(asn1.Class.CONTEXT_SPECIFIC, 2, false, dnsName)
+    ))));
+  }
+  extensions.push(_extension('2.5.29.14', false, _octetString(subjectKeyId)));
+  extensions.push(_extension('2.5.29.35', false, _sequence([
+    asn1.create(asn1.Class.CONTEXT_SPECIFIC, 0, false, authorityKeyId),
+  ])));
+  return extensions;
+}
+
+/**
+ * Encrypt PKCS#8 DER as an ENCRYPTED PRIVATE KEY PEM (PBES2 with PBKDF2-HMAC-SHA256
+ * and AES-256-CBC), readable by OpenSSL and by the parser's password handling
+ */
+async function _encryptPrivateKey(pkcs8Der, password) {
+  const subtle = globalThis.crypto.subtle;
+  const salt = globalThis.crypto.getRandomValues(new Uint8Array(16));
+  const iv = globalThis.crypto.getRandomValues(new Uint8Array(16));
+
+  const passwordKey = await subtle.importKey('raw', new TextEncoder().encode(password), 'PBKDF2', false, ['deriveKey']);
+  const aesKey = await subtle.deriveKey(
+    { name: 'PBKDF2', salt, iterations: KEY_ENCRYPTION_ITERATIONS, hash: 'SHA-256' },
+    passwordKey, { name: 'AES-CBC', length: 256 }, false, ['encrypt']);
+  const encrypted = _fromBytes(await subtle.encrypt({ name: 'AES-CBC', iv }, aesKey, toBytes(pkcs8Der)));
+
+  const encryptedPrivateKeyInfo = _sequence([
+    _sequence([
+      _oid('1.2.840.113549.1.5.13'),
+      _sequence([
+        _sequence([
+          _oid('1.2.840.113549.1.5.12'),
+          _sequence([
+            _octetString(_fromBytes(salt)),
+            _integer(asn1.integerToDer(KEY_ENCRYPTION_ITERATIONS).getBytes()),
+            _sequence([_oid('1.2.840.113549.2.9'), asn1.create(asn1.Class.UNIVERSAL, asn1.Type.NULL, false, '')]),
+          ]),
+        ]),
+        _sequence([_oid('2.16.840.1.101.3.4.1.42'), _octetString(_fromBytes(iv))]),
+      ]),
+    ]),
+    _octetString(encrypted),
+  ]);
+  return toPem(toBytes(asn1.toDer(encryptedPrivateKeyInfo).getBytes()), 'ENCRYPTED PRIVATE KEY');
+}
+
+/**
+ * Import a PKCS#8 or SEC1 EC private key, or a PKCS#8 / PKCS#1 RSA key, for signing
+ */
+async function _importSigningKey(pem) {
+  if (pem.includes('ENCRYPTED')) {
+    throw new Error('Private key is encrypted');
+  }
+  let der = _pemToDer(pem);
+  let parameters;
+
+  if (pem.includes('EC PRIVATE KEY')) {
+    // Wrap SEC1 in PKCS#8; the curve comes from the [0] parameters
+    const ecKey = fromDerRaw(der);
+    const curve = ecKey.value.find(node => node.tagClass === asn1.Class.CONTEXT_SPECIFIC && node.type === 0);
+    der = asn1.toDer(_sequence([
+      _integer('\x00'),
+      _sequence([_oid('1.2.840.10045.2.1'), curve.value[0]]),
+      _octetString(der),
+    ])).getBytes();
+  } else if (pem.includes('RSA PRIVATE KEY')) {
+    der = asn1.toDer(_sequence([
+      _integer('\x00'),
+      _sequence([_oid('1.2.840.113549.1.1.1'), asn1.create(asn1.Class.UNIVERSAL, asn1.Type.NULL, false, '')]),
+      _octetString(der),
+    ])).getBytes();
+  }
+
+  const algorithm = fromDerRaw(der).value[1];
+  const keyOid = asn1.derToOid(algorithm.value[0].value);
+  if (keyOid === '1.2.840.10045.2.1') {
+    parameters = EC_CURVES[asn1.derToOid(algorithm.value[1].value)];
+    if (!parameters) {
+      throw new Error('Unsupported EC curve');
+    }
+    const key = await globalThis.crypto.subtle.importKey('pkcs8', toBytes(der),
+      { name: 'ECDSA', namedCurve: parameters.namedCurve }, false, ['sign']);
+    return { key, ...parameters, kind: 'EC' };
+  }
+  if (keyOid === '1.2.840.113549.1.1.1') {
+    const key = await globalThis.crypto.subtle.importKey('pkcs8', toBytes(der),
+      { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-256' }, false, ['sign']);
+    return { key, hash: 'SHA-256', signatureOid: '1.2.840.113549.1.1.11', kind: 'RSA' };
+  }
+  throw new Error(`Unsupported private key algorithm: ${keyOid}`);
+}
+
+function _signatureAlgorithm(signer) {
+  const fields = [_oid(signer.signatureOid)];
+  if (signer.kind === 'RSA') {
+    fields.push(asn1.create(asn1.Class.UNIVERSAL, asn1.Type.NULL, false, ''));
+  }
+  return _sequence(fields);
+}
+
+/**
+ * Sign with WebCrypto, converting ECDSA's r || s output to a DER ECDSA-Sig-Value
+ */
+async function _sign(signer, data) {
+  const params = signer.kind === 'EC' ? { name: 'ECDSA', hash: signer.hash } : { name: 'RSASSA-PKCS1-v1_5' };
+  const signature = _fromBytes(await globalThis.crypto.subtle.sign(params, signer.key, toBytes(data)));
+  if (signer.kind !== 'EC') {
+    return signature;
+  }
+
+  const toInteger = (bytes) => {
+    const trimmed = bytes.replace(/^\x00+/, '') || '\x00';
+    return _integer(trimmed.charCodeAt(0) & 0x80 ? '\x00' + trimmed : trimmed);
+  };
+  return asn1.toDer(_sequence([
+    toInteger(signature.substring(0, signer.size)),
+    toInteger(signature.substring(signer.size)),
+  ])).getBytes();
+}
+
+/**
+ * Assemble, sign and PEM-encode a certificate
+ */
+async function _issueCertificate({ subjectName, issuerName, spkiDer, signer, options, isCA, dnsNames, authorityKeyId }) {
+  const { notBefore, notAfter } = _validityPeriod(options);
+  const subjectKeyId = _keyIdentifier(spkiDer);
+
+  const tbs = _sequence([
+    asn1.create(asn1.Class.CONTEXT_SPECIFIC, 0, true, [_integer('\x02')]),
+    _integer(_serialNumber(options.serialNumber)),
+    _signatureAlgorithm(signer),
+    issuerName,
+    _sequence([_time(notBefore), _time(notAfter)]),
+    subjectName,
+    fromDerRaw(spkiDer),
+    asn1.create(asn1.Class.CONTEXT_SPECIFIC, 3, true, [
+      _sequence(_extensions({
+        isCA,
+        pathLength: options.pathLength,
+        keyUsage: options.keyUsage,
+        dnsNames,
+        // A CA only gets an EKU (constraining what it may issue for) when one is asked for
+        extendedKeyUsage: isCA && options.extendedKeyUsage === undefined
+          ? null
+          : _extendedKeyUsageOids(options.extendedKeyUsage),
+        subjectKeyId,
+        authorityKeyId: authorityKeyId || subjectKeyId,
+      })),
+    ]),
+  ]);
+
+  const tbsDer = asn1.toDer(tbs).getBytes();
+  const signature = await _sign(signer, tbsDer);
+  const certificate = _sequence([tbs, _signatureAlgorithm(signer), _bitString(signature)]);
+
+  return toPem(toBytes(asn1.toDer(certificate).getBytes()), 'CERTIFICATE');
+}
+
+/**
+ * Generate a fresh private key for an ACME account or certificate:
+ * 'ec-p256' (the default, what most ACME clients use) or 'rsa-2048'.
+ * Resolves to an unencrypted PKCS#8 PEM.
+ */
+export async function generateAcmeKey(algorithm = 'ec-p256') {
+  const parameters = ACME_KEY_ALGORITHMS[algorithm];
+  if (!parameters) {
+    throw new Error(`Unsupported ACME key algorithm: ${algorithm} (use ${Object.keys(ACME_KEY_ALGORITHMS).join(' or ')})`);
+  }
+  const subtle = globalThis.crypto.subtle;
+  const keyPair = await subtle.generateKey(parameters, true, ['sign', 'verify']);
+  return toPem(await subtle.exportKey('pkcs8', keyPair.privateKey), 'PRIVATE KEY');
+}
+
+/**
+ * Generate an EC P-256 key and a self-signed certificate.
+ *
+ * Options: subject ({ CN, O, OU, C, ST, L }), dnsNames, isCA, and either
+ * validityDays (default 365, starting now) or explicit notBefore/notAfter.
+ * serialNumber (hex) defaults to 16 random bytes. extendedKeyUsage lists key
+ * purpose names (e.g. ['clientAuth']) or dotted OIDs, default ['serverAuth'];
+ * a CA gets no EKU unless one is given.
+ * keyUsage lists key usage names and pathLength limits a CA's path length.
+ *
+ * profile ('tls-server', 'tls-client', 'ca-root' or 'ca-intermediate') presets
+ * isCA, keyUsage, extendedKeyUsage and pathLength; explicit options win.
+ * With keyPassword the key is returned as an ENCRYPTED PRIVATE KEY (PBES2, AES-256).
+ * Resolves to { certificatePem, privateKeyPem }.
+ */
+export async function generateSelfSigned(userOptions = {}) {
+  const subtle = globalThis.crypto.subtle;
+  const options = _withProfile(userOptions);
+  // Validate the serial, dates and key purposes before doing any key generation
+  _serialNumber(options.serialNumber);
+  _validityPeriod(options);
+  _extendedKeyUsageOids(options.extendedKeyUsage);
+
+  const keyPair = await subtle.generateKey({ name: 'ECDSA', namedCurve: 'P-256' }, true, ['sign', 'verify']);
+  const pkcs8Der = _fromBytes(await subtle.exportKey('pkcs8', keyPair.privateKey));
+  const privateKeyPem = toPem(toBytes(pkcs8Der), 'PRIVATE KEY');
+  const spkiDer = _fromBytes(await subtle.exportKey('spki', keyPair.publicKey));
+  const subject = options.subject || { CN: (options.dnsNames && options.dnsNames[0]) || 'localhost' };
+
+  const certificatePem = await _issueCertificate({
+    subjectName: _name(subject),
+    issuerName: _name(subject),
+    spkiDer,
+    signer: await _importSigningKey(privateKeyPem),
+    options,
+    isCA: options.isCA === true,
+    dnsNames: options.dnsNames || [],
+  });
+
+  if (options.keyPassword) {
+    return { certificatePem, privateKeyPem: await _encryptPrivateKey(pkcs8Der, options.keyPassword) };
+  }
+  return { certificatePem, privateKeyPem };
+}
+
+/**
+ * Read the subject, public key and requested SANs from a PKCS#10 CSR, and
+ * check its self-signature (signatureValid is null for unsupported algorithms)
+ */
+async function _readCsr(csrPem, maxInputBytes) {
+  checkInputSize(csrPem, maxInputBytes);
+  checkNotEmpty(csrPem);
+  const csr = fromDerRaw(_pemToDer(csrPem));
+  const [info, signatureAlgorithm, signature] = csr.value;
+  const [, subject, spki, attributes] = info.value;
+
+  let signatureValid;
+  try {
+    signatureValid = await verifySignature(
+      asn1.toDer(spki).getBytes(),
+      asn1.derToOid(signatureAlgorithm.value[0].value),
+      asn1.toDer(info).getBytes(),
+      signature.value.substring(1)
+    );
+  } catch {
+    signatureValid = null;
+  }
+
+  // subjectAltName from the extensionRequest attribute, if any
+  const dnsNames = [];
+  for (const attribute of attributes ? attributes.value : []) {
+    if (asn1.derToOid(attribute.value[0].value) !== '1.2.840.113549.1.9.14') {
+      continue;
+    }
+    for (const extension of attribute.value[1].value[0].value) {
+      if (asn1.derToOid(extension.value[0].value) === '2.5.29.17') {
+        const names = fromDerRaw(extension.value[extension.value.length - 1].value);
+        names.value.filter(name => name.type === 2).forEach(name => dnsNames.push(name.value));
+      }
+    }
+  }
+
+  return { subject, spki, spkiDer: asn1.toDer(spki).getBytes(), dnsNames, signatureValid };
+}
+
+/**
+ * Name attributes as { shortName: value }, with the OID for unknown attributes
+ */
+function _nameToObject(name) {
+  const shortNames = Object.fromEntries(SUBJECT_ATTRIBUTES.map(([shortName, oid]) => [oid, shortName]));
+  const result = {};
+  for (const rdn of name.value) {
+    for (const attribute of rdn.value) {
+      const oid = asn1.derToOid(attribute.value[0].value);
+      const value = attribute.value[1];
+      result[shortNames[oid] || oid] = value.type === asn1.Type.UTF8 ? forge.util.decodeUtf8(value.value) : value.value;
+    }
+  }
+  return result;
+}
+
+/**
+ * Check a CSR before submitting it to a CA: its self-signature, key strength
+ * (RSA below 2048 bits or an unknown curve is weak), and what it requests.
+ * Resolves to { signatureValid, keyAlgorithm, keySizeBits, curve, weakKey, subject, dnsNames }.
+ * Pass { maxInputBytes } to change the input size limit (DEFAULT_MAX_INPUT_BYTES).
+ */
+export async function validateCsr(csrPem, options = {}) {
+  const csr = await _readCsr(csrPem, options.maxInputBytes);
+  const { algorithm, sizeBits, curve } = publicKeyDetails(csr.spki);
+  return {
+    signatureValid: csr.signatureValid,
+    keyAlgorithm: algorithm,
+    keySizeBits: sizeBits,
+    curve,
+    // An unknown algorithm or curve has no size and counts as weak
+    weakKey: sizeBits === null || (algorithm === 'RSA' && sizeBits < MIN_RSA_KEY_BITS),
+    subject: _nameToObject(csr.subject),
+    dnsNames: csr.dnsNames,
+  };
+}
+
+/**
+ * Issue a certificate for a CSR, signed by a CA certificate and its private key.
+ *
+ * Options: dnsNames (defaults to the CSR's requested SANs), and as for
+ * generateSelfSigned profile, isCA, keyUsage, extendedKeyUsage, pathLength,
+ * validityDays or explicit notBefore/notAfter, serialNumber (hex), and
+ * maxInputBytes for the CSR and issuer certificate.
+ * Resolves to the certificate PEM.
+ */
+export async function signCsr(csrPem, issuerCertPem, issuerKeyPem, userOptions = {}) {
+  const options = _withProfile(userOptions);
+  _serialNumber(options.serialNumber);
+  _validityPeriod(options);
+  _extendedKeyUsageOids(options.extendedKeyUsage);
+
+  const csr = await _readCsr(csrPem, options.maxInputBytes);
+  if (!csr.signatureValid) {
+    throw new Error('CSR signature is invalid');
+  }
+  const issuerCert = certificateFromPem(issuerCertPem, options);
+  const issuerInfo = extractCertificateInfo(issuerCert);
+  const issuerSpki = asn1.toDer(tbsCertificateFields(issuerCert.der).subjectPublicKeyInfo).getBytes();
+
+  return _issueCertificate({
+    subjectName: csr.subject,
+    issuerName: tbsCertificateFields(issuerCert.der).subject,
+    spkiDer: csr.spkiDer,
+    signer: await _importSigningKey(issuerKeyPem),
+    options,
+    isCA: options.isCA === true,
+    dnsNames: options.dnsNames || csr.dnsNames,
+    authorityKeyId: issuerInfo.subjectKeyIdentifier
+      ? forge.util.hexToBytes(issuerInfo.subjectKeyIdentifier)
+      : _keyIdentifier(issuerSpki),
+  });
+}
+
+/**
+ * Check an issued certificate against the CSR it was requested with: same
+ * public key, same subject attributes (a CSR with an empty subject accepts
+ * any) and every requested DNS SAN present.
+ * Resolves to { matches, publicKeyMatches, subjectMatches, missingSans }.
+ * Pass { maxInputBytes } to change the input size limit.
+ */
+export async function certMatchesCsr(certPem, csrPem, options = {}) {
+  const csr = await _readCsr(csrPem, options.maxInputBytes);
+  const cert = certificateFromPem(certPem, options);
+  const fields = tbsCertificateFields(cert.der);
+  const sortedAttributes = (name) => JSON.stringify(Object.entries(_nameToObject(name)).sort());
+
+  const publicKeyMatches = asn1.toDer(fields.subjectPublicKeyInfo).getBytes() === csr.spkiDer;
+  const subjectMatches = csr.subject.value.length === 0 || sortedAttributes(csr.subject) === sortedAttributes(fields.subject);
+  const issuedNames = extractCertificateInfo(cert).dnsNames.map(name => name.toLowerCase());
+  const missingSans = csr.dnsNames.filter(name => !issuedNames.includes(name.toLowerCase()));
+
+  return {
+    matches: publicKeyMatches && subjectMatches && missingSans.length === 0,
+    publicKeyMatches,
+    subjectMatches,
+    missingSans,
+  };
+}
+
+/**
+ * Build a signed v2 CRL for a CA (for demo PKIs): the issuer is the CA's
+ * subject, with authorityKeyIdentifier and cRLNumber extensions.
+ *
+ * revoked lists { serialNumber (hex), reason (a REVOCATION_REASONS name,
+ * optional), revocationDate (default thisUpdate) }. Options: thisUpdate
+ * (default now), nextUpdate (default 7 days later) and crlNumber (default 1).
+ * Resolves to the CRL PEM.
+ */
+export async function buildCrl(caCertPem, caKeyPem, revoked = [], options = {}) {
+  const thisUpdate = options.thisUpdate !== undefined ? new Date(options.thisUpdate) : new Date();
+  const nextUpdate = options.nextUpdate !== undefined
+    ? new Date(options.nextUpdate)
+    : new Date(thisUpdate.getTime() + 7 * DAY_MS);
+  if (isNaN(thisUpdate.getTime()) || isNaN(nextUpdate.getTime())) {
+    throw new Error('Invalid CRL update time');
+  }
+  if (nextUpdate <= thisUpdate) {
+    throw new Error('nextUpdate must be later than thisUpdate');
+  }
+
+  const reasonCodes = Object.fromEntries(Object.entries(REVOCATION_REASONS).map(([code, name]) => [name, Number(code)]));
+  const entries = revoked.map(({ serialNumber, reason, revocationDate }) => {
+    if (serialNumber === undefined || serialNumber === null) {
+      throw new Error('Revoked entries need a serialNumber');
+    }
+    if (reason !== undefined && reasonCodes[reason] === undefined) {
+      throw new Error(`Unknown revocation reason: ${reason}`);
+    }
+    const fields = [
+      _integer(_serialNumber(serialNumber)),
+      _time(revocationDate !== undefined ? new Date(revocationDate) : thisUpdate),
+    ];
+    if (reason !== undefined) {
+      fields.push(_sequence([_extension('2.5.29.21', false,
+        asn1.create(asn1.Class.UNIVERSAL, asn1.Type.ENUMERATED, false, String.fromCharCode(reasonCodes[reason])))]));
+    }
+    return _sequence(fields);
+  });
+
+  const caCert = certificateFromPem(caCertPem);
+  const caInfo = extractCertificateInfo(caCert);
+  const caFields = tbsCertificateFields(caCert.der);
+  const authorityKeyId = caInfo.subjectKeyIdentifier
+    ? forge.util.hexToBytes(caInfo.subjectKeyIdentifier)
+    : _keyIdentifier(asn1.toDer(caFields.subjectPublicKeyInfo).getBytes());
+  const signer = await _importSigningKey(caKeyPem);
+
+  const tbs = _sequence([
+    _integer('\x01'),
+    _signatureAlgorithm(signer),
+    caFields.subject,
+    _time(thisUpdate),
+    _time(nextUpdate),
+    ...(entries.length > 0 ? [_sequence(entries)] : []),
+    asn1.create(asn1.Class.CONTEXT_SPECIFIC, 0, true, [
+      _sequence([
+        _extension('2.5.29.35', false, _sequence([
+          asn1.create(asn1.Class.CONTEXT_SPECIFIC, 0, false, authorityKeyId),
+        ])),
+        _extension('2.5.29.20', false, _integer(asn1.integerToDer(options.crlNumber ?? 1).getBytes())),
+      ]),
+    ]),
+  ]);
+
+  const signature = await _sign(signer, asn1.toDer(tbs).getBytes());
+  const crl = _sequence([tbs, _signatureAlgorithm(signer), _bitString(signature)]);
+  return toPem(toBytes(asn1.toDer(crl).getBytes()), 'X509 CRL');
+}
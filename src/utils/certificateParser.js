@@ -1,9 +1,49 @@
 import forge from 'node-forge';
+import { CertificateError, CertificateErrorCode } from './errors.js';
 
 /**
  * Parse certificate files and extract certificate information
  */
 
+/**
+ * Build a Date straight from the components of an ASN.1 time. Unlike forge's
+ * parsers this handles omitted minutes/seconds, fractional seconds (with either
+ * separator) and explicit +hhmm offsets. No offset means UTC.
+ */
+function _timeFromComponents(value, isUtcTime) {
+  const match = value.match(/^(\d{2}|\d{4})(\d{2})(\d{2})(\d{2})(\d{2})?(\d{2})?(?:[.,](\d+))?(Z|[+-]\d{4})?$/);
+  if (!match || match[1].length !== (isUtcTime ? 2 : 4)) {
+    return new Date(NaN);
+  }
+  const [, yearText, month, day, hour, minute = '0', second = '0', fraction = '0', zone = 'Z'] = match;
+  let year = parseInt(yearText, 10);
+  if (isUtcTime) {
+    year += year >= 50 ? 1900 : 2000;
+  }
+
+  const date = new Date(0);
+  date.setUTCFullYear(year, parseInt(month, 10) - 1, parseInt(day, 10));
+  date.setUTCHours(parseInt(hour, 10), parseInt(minute, 10), parseInt(second, 10), Math.round(parseFloat(`0.${fraction}`) * 1000));
+  if (zone !== 'Z') {
+    const offsetMinutes = parseInt(zone.substring(1, 3), 10) * 60 + parseInt(zone.substring(3), 10);
+    date.setTime(date.getTime() - (zone[0] === '+' ? 1 : -1) * offsetMinutes * 60000);
+  }
+  return date;
+}
+
+/**
+ * Parse a UTCTime or GeneralizedTime node from its components, falling back to
+ * forge for anything else, so a parseable time never shows as "Invalid Date"
+ */
+export function parseTime(node) {
+  const isUtcTime = node.type === forge.asn1.Type.UTCTIME;
+  const date = _timeFromComponents(node.value, isUtcTime);
+  if (!isNaN(date.getTime())) {
+    return date;
+  }
+  return isUtcTime ? forge.asn1.utcTimeToDate(node.value) : forge.asn1.generalizedTimeToDate(node.value);
+}
+
 /**
  * Parse certificate from ASN.1 with support for non-RSA keys (EC, EdDSA, etc.)
  * This function manually extracts certificate information without relying on
@@ -12,7 +52,12 @@ import forge from 'node-forge';
 function safeCertificateFromAsn1(asn1) {
   // First try the standard method for RSA certificates
   try {
-    return forge.pki.certificateFromAsn1(asn1);
+    const cert = forge.pki.certificateFromAsn1(asn1);
+    const tbsCert = asn1.value[0];
+    const validity = tbsCert.value[tbsCert.value[0].tagClass === forge.asn1.Class.CONTEXT_SPECIFIC ? 4 : 3];
+    cert.validity.notBefore = parseTime(validity.value[0]);
+    cert.validity.notAfter = parseTime(validity.value[1]);
+    return cert;
   } catch (e) {
     // If it fails due to non-RSA key, parse manually
     if (!e.message || !e.message.includes('Cannot read public key')) {
@@ -52,10 +97,8 @@ function safeCertificateFromAsn1(asn1) {
   // Validity
   const validity = tbsCert.value[idx++];
   capture.certValidity = {
-    notBefore: forge.asn1.utcTimeToDate(validity.value[0].value) || 
-               forge.asn1.generalizedTimeToDate(validity.value[0].value),
-    notAfter: forge.asn1.utcTimeToDate(validity.value[1].value) || 
-              forge.asn1.generalizedTimeToDate(validity.value[1].value)
+    notBefore: parseTime(validity.value[0]),
+    notAfter: parseTime(validity.value[1]),
   };
 
   // Subject
@@ -130,21 +173,36 @@ function _parseRDNSequence(rdn) {
   return attributes;
 }
 
+const NAME_SHORT_NAMES = {
+  '2.5.4.3': 'CN',
+  '2.5.4.6': 'C',
+  '2.5.4.7': 'L',
+  '2.5.4.8': 'ST',
+  '2.5.4.10': 'O',
+  '2.5.4.11': 'OU',
+  '2.5.4.5': 'serialNumber',
+  '1.2.840.113549.1.9.1': 'emailAddress'
+};
+
 /**
  * Get short name for OID
  */
 function _getShortName(oid) {
-  const shortNames = {
-    '2.5.4.3': 'CN',
-    '2.5.4.6': 'C',
-    '2.5.4.7': 'L',
-    '2.5.4.8': 'ST',
-    '2.5.4.10': 'O',
-    '2.5.4.11': 'OU',
-    '2.5.4.5': 'serialNumber',
-    '1.2.840.113549.1.9.1': 'emailAddress'
-  };
-  return shortNames[oid] || forge.pki.oids[oid] || oid;
+  return NAME_SHORT_NAMES[oid] || forge.pki.oids[oid] || oid;
+}
+
+/**
+ * OID for an attribute type as written in a DN string (short name, forge
+ * name, "OID.1.2.3" or dotted OID); unknown names are returned uppercased
+ */
+function _nameTypeOid(type) {
+  const dotted = type.replace(/^oid\./i, '');
+  if (/^\d+(\.\d+)+$/.test(dotted)) {
+    return dotted;
+  }
+  const oid = Object.keys(NAME_SHORT_NAMES).find(key => NAME_SHORT_NAMES[key].toUpperCase() === type.toUpperCase());
+  const forgeOid = forge.pki.oids[type];
+  return oid || (typeof forgeOid === 'string' && /^\d+(\.\d+)+$/.test(forgeOid) ? forgeOid : type.toUpperCase());
 }
 
 /**
@@ -190,343 +248,2547 @@ function _parseExtensions(extSeq) {
 }
 
 /**
- * Safely convert certificate to PEM, handling non-RSA keys
+ * CA/Browser Forum certificate policies, which node-forge does not know: the
+ * friendly name of each and the validation level it asserts
  */
-function safeCertificateToPem(asn1) {
-  // For certificates with non-RSA keys, we need to convert the raw ASN.1 back to PEM
-  // since certificateToPem won't work with our manually parsed certificate
-  const der = forge.asn1.toDer(asn1);
-  const pem = forge.util.encode64(der.getBytes(), 64);
-  return '-----BEGIN CERTIFICATE-----\n' + pem + '\n-----END CERTIFICATE-----';
+const CABF_POLICIES = {
+  '2.23.140.1.1': { name: 'extended-validation', validationLevel: 'EV' },
+  '2.23.140.1.2.1': { name: 'domain-validated', validationLevel: 'DV' },
+  '2.23.140.1.2.2': { name: 'organization-validated', validationLevel: 'OV' },
+  '2.23.140.1.2.3': { name: 'individual-validated', validationLevel: 'IV' },
+};
+
+/**
+ * Friendly name for an OID, preferring caller-supplied overrides
+ */
+function _oidName(oid, oidNames = {}) {
+  return oidNames[oid] || (CABF_POLICIES[oid] && CABF_POLICIES[oid].name) || forge.pki.oids[oid] || null;
 }
 
-// Try to parse PEM format
-function parsePEM(data) {
-  const certificates = [];
-  const privateKeys = [];
-  
-  try {
-    // Try to parse as PEM
-    const lines = data.split('\n');
-    let currentBlock = [];
-    let inBlock = false;
-    let blockType = null;
+/**
+ * Signature algorithm OIDs and the digest each one uses
+ */
+export const SIGNATURE_ALGORITHMS = {
+  '1.2.840.113549.1.1.2': { name: 'md2WithRSAEncryption', hash: 'md2' },
+  '1.2.840.113549.1.1.4': { name: 'md5WithRSAEncryption', hash: 'md5' },
+  '1.2.840.113549.1.1.5': { name: 'sha1WithRSAEncryption', hash: 'sha1' },
+  '1.3.14.3.2.29': { name: 'sha1WithRSASignature', hash: 'sha1' },
+  '1.2.840.113549.1.1.10': { name: 'RSASSA-PSS', hash: null },
+  '1.2.840.113549.1.1.11': { name: 'sha256WithRSAEncryption', hash: 'sha256' },
+  '1.2.840.113549.1.1.12': { name: 'sha384WithRSAEncryption', hash: 'sha384' },
+  '1.2.840.113549.1.1.13': { name: 'sha512WithRSAEncryption', hash: 'sha512' },
+  '1.2.840.113549.1.1.14': { name: 'sha224WithRSAEncryption', hash: 'sha224' },
+  '1.2.840.10040.4.3': { name: 'dsa-with-sha1', hash: 'sha1' },
+  '2.16.840.1.101.3.4.3.2': { name: 'dsa-with-sha256', hash: 'sha256' },
+  '1.2.840.10045.4.1': { name: 'ecdsa-with-SHA1', hash: 'sha1' },
+  '1.2.840.10045.4.3.1': { name: 'ecdsa-with-SHA224', hash: 'sha224' },
+  '1.2.840.10045.4.3.2': { name: 'ecdsa-with-SHA256', hash: 'sha256' },
+  '1.2.840.10045.4.3.3': { name: 'ecdsa-with-SHA384', hash: 'sha384' },
+  '1.2.840.10045.4.3.4': { name: 'ecdsa-with-SHA512', hash: 'sha512' },
+  '1.3.101.112': { name: 'Ed25519', hash: null },
+  '1.3.101.113': { name: 'Ed448', hash: null },
+};
 
-    for (const line of lines) {
-      const trimmed = line.trim();
-      
-      if (trimmed.startsWith('-----BEGIN')) {
-        inBlock = true;
-        blockType = trimmed;
-        currentBlock = [line];
-      } else if (trimmed.startsWith('-----END')) {
-        currentBlock.push(line);
-        inBlock = false;
-        
-        const pemBlock = currentBlock.join('\n');
-        
-        if (blockType.includes('CERTIFICATE')) {
-          try {
-            // Extract the base64 content from PEM
-            const pemContent = pemBlock
-              .replace(/-----BEGIN CERTIFICATE-----/, '')
-              .replace(/-----END CERTIFICATE-----/, '')
-              .replace(/\s/g, '');
-            const der = forge.util.decode64(pemContent);
-            const asn1 = forge.asn1.fromDer(der);
-            
-            // Use safe parsing that handles non-RSA keys
-            const cert = safeCertificateFromAsn1(asn1);
-            
-            certificates.push({
-              type: 'certificate',
-              data: cert,
-              pem: pemBlock,
-            });
-          } catch (e) {
-            console.warn('Failed to parse certificate:', e);
-          }
-        } else if (blockType.includes('PRIVATE KEY') || blockType.includes('RSA PRIVATE KEY')) {
-          privateKeys.push({
-            type: 'privateKey',
-            pem: pemBlock,
-            encrypted: blockType.includes('ENCRYPTED'),
-          });
-        }
-        
-        currentBlock = [];
-        blockType = null;
-      } else if (inBlock) {
-        currentBlock.push(line);
-      }
-    }
-  } catch (e) {
-    console.error('Error parsing PEM:', e);
+// Largest input the exported parsers accept unless options.maxInputBytes says otherwise
+export const DEFAULT_MAX_INPUT_BYTES = 32 * 1024 * 1024;
+
+/**
+ * Length of a string in UTF-8 bytes, counted without encoding it
+ */
+function _utf8Length(text) {
+  let length = 0;
+  for (let i = 0; i < text.length; i++) {
+    const code = text.charCodeAt(i);
+    // Each half of a surrogate pair counts 2 of the pair's 4 bytes
+    length += code < 0x80 ? 1 : code < 0x800 || (code >= 0xd800 && code <= 0xdfff) ? 2 : 3;
+  }
+  return length;
+}
+
+/**
+ * Throw an INPUT_TOO_LARGE CertificateError when `input` (text, bytes or a
+ * File / Blob) is over `maxInputBytes`, before any decoding is attempted.
+ * Text is measured in UTF-8 bytes.
+ */
+export function checkInputSize(input, maxInputBytes = DEFAULT_MAX_INPUT_BYTES) {
+  let size = 0;
+  if (typeof input === 'string') {
+    size = _utf8Length(input);
+  } else if (input) {
+    size = input.byteLength ?? input.size ?? input.length ?? 0;
   }
+  if (size > maxInputBytes) {
+    throw new CertificateError(
+      CertificateErrorCode.INPUT_TOO_LARGE,
+      `Input too large: ${size} bytes (limit ${maxInputBytes})`
+    );
+  }
+}
 
-  return { certificates, privateKeys };
+function _isWhitespaceByte(byte) {
+  return byte === 0x20 || (byte >= 0x09 && byte <= 0x0d);
 }
 
-// Try to parse DER format
-function parseDER(arrayBuffer) {
-  const certificates = [];
-  
-  try {
-    const asn1 = forge.asn1.fromDer(forge.util.createBuffer(arrayBuffer));
-    const cert = safeCertificateFromAsn1(asn1);
-    const pem = safeCertificateToPem(asn1);
-    
-    certificates.push({
-      type: 'certificate',
-      data: cert,
-      pem: pem,
-    });
-  } catch (e) {
-    console.warn('Not a DER certificate:', e);
+/**
+ * Throw an EMPTY_INPUT CertificateError, naming the input as `name`, when
+ * `input` (text or bytes) is empty or only whitespace, rather than letting
+ * the decoder fail on it
+ */
+export function checkNotEmpty(input, name = 'Input') {
+  let empty = false;
+  if (typeof input === 'string') {
+    empty = input.trim() === '';
+  } else if (input instanceof ArrayBuffer || ArrayBuffer.isView(input)) {
+    const bytes = input instanceof ArrayBuffer ? new Uint8Array(input) : new Uint8Array(input.buffer, input.byteOffset, input.byteLength);
+    empty = bytes.every(_isWhitespaceByte);
+  }
+  if (empty) {
+    throw new CertificateError(CertificateErrorCode.EMPTY_INPUT, `${name} is empty`);
   }
+}
 
-  return { certificates, privateKeys: [] };
+/**
+ * Decode DER without forge's BIT STRING auto-decoding, which can misread
+ * flag bits (e.g. keyUsage) as nested ASN.1
+ */
+export function fromDerRaw(bytes) {
+  return forge.asn1.fromDer(bytes, { decodeBitStrings: false });
 }
 
-// Try to parse PKCS#12/PFX format
-async function parsePKCS12(arrayBuffer, password = '') {
-  const certificates = [];
-  const privateKeys = [];
-  
-  try {
-    const p12Der = forge.util.createBuffer(arrayBuffer);
-    const p12Asn1 = forge.asn1.fromDer(p12Der);
-    const p12 = forge.pkcs12.pkcs12FromAsn1(p12Asn1, password);
+/**
+ * Find an extension by OID
+ */
+function _findExtension(cert, oid) {
+  return cert.extensions.find(ext => ext.id === oid);
+}
 
-    // Extract certificates
-    const certBags = p12.getBags({ bagType: forge.pki.oids.certBag });
-    for (const bagType in certBags) {
-      for (const bag of certBags[bagType]) {
-        if (bag.cert) {
-          try {
-            // Try standard PEM conversion
-            const pem = forge.pki.certificateToPem(bag.cert);
-            certificates.push({
-              type: 'certificate',
-              data: bag.cert,
-              pem: pem,
-            });
-          } catch (e) {
-            // For non-RSA certificates, convert from ASN.1
-            if (bag.asn1) {
-              const cert = safeCertificateFromAsn1(bag.asn1);
-              const pem = safeCertificateToPem(bag.asn1);
-              certificates.push({
-                type: 'certificate',
-                data: cert,
-                pem: pem,
-              });
-            } else {
-              console.warn('Failed to convert PKCS#12 certificate to PEM:', e);
-            }
-          }
-        }
-      }
-    }
+// Bidi embedding, override and isolate controls and implicit direction marks,
+// which can make a name display differently from what it contains
+const BIDI_CONTROL_CODE_POINTS = new Set([
+  0x061c, 0x200e, 0x200f, 0x202a, 0x202b, 0x202c, 0x202d, 0x202e, 0x2066, 0x2067, 0x2068, 0x2069,
+]);
 
-    // Extract private keys
-    const keyBags = p12.getBags({ bagType: forge.pki.oids.pkcs8ShroudedKeyBag });
-    for (const bagType in keyBags) {
-      for (const bag of keyBags[bagType]) {
-        if (bag.key) {
-          const pem = forge.pki.privateKeyToPem(bag.key);
-          privateKeys.push({
-            type: 'privateKey',
-            pem: pem,
-            encrypted: false,
-          });
-        }
-      }
-    }
+function _isSuspiciousNameChar(char) {
+  const code = char.codePointAt(0);
+  return code < 0x20 || (code >= 0x7f && code <= 0x9f) || BIDI_CONTROL_CODE_POINTS.has(code);
+}
 
-    // Also check for unencrypted key bags
-    const unencryptedKeyBags = p12.getBags({ bagType: forge.pki.oids.keyBag });
-    for (const bagType in unencryptedKeyBags) {
-      for (const bag of unencryptedKeyBags[bagType]) {
-        if (bag.key) {
-          const pem = forge.pki.privateKeyToPem(bag.key);
-          privateKeys.push({
-            type: 'privateKey',
-            pem: pem,
-            encrypted: false,
-          });
-        }
-      }
-    }
-  } catch (e) {
-    if (e.message && e.message.includes('Invalid password')) {
-      throw new Error('INVALID_PASSWORD');
-    }
-    throw e;
+// Name values are UTF-8 byte strings; decode them to see the actual characters
+function _nameValueText(value) {
+  try {
+    return { text: forge.util.decodeUtf8(value), utf8: true };
+  } catch {
+    return { text: value, utf8: false };
   }
+}
 
-  return { certificates, privateKeys };
+function _isSuspiciousNameValue(value) {
+  return typeof value === 'string' && Array.from(_nameValueText(value).text).some(_isSuspiciousNameChar);
 }
 
-// Extract certificate information for display
-export function extractCertificateInfo(cert) {
-  const subject = cert.subject.attributes.reduce((acc, attr) => {
-    acc[attr.shortName || attr.name] = attr.value;
-    return acc;
-  }, {});
+/**
+ * Escape C0/C1 control characters and bidi controls in a name value as \uXXXX,
+ * so that a spoofed name cannot be rendered as something else. Backslashes
+ * already in such a value are doubled, so a literal "\u" stays distinct.
+ */
+function _sanitizeNameValue(value) {
+  if (!_isSuspiciousNameValue(value)) {
+    return value;
+  }
+  const { text, utf8 } = _nameValueText(value);
+  const escaped = Array.from(text, char => {
+    if (char === '\\') {
+      return '\\\\';
+    }
+    return _isSuspiciousNameChar(char)
+      ? `\\u${char.codePointAt(0).toString(16).toUpperCase().padStart(4, '0')}`
+      : char;
+  }).join('');
+  return utf8 ? forge.util.encodeUtf8(escaped) : escaped;
+}
 
-  const issuer = cert.issuer.attributes.reduce((acc, attr) => {
-    acc[attr.shortName || attr.name] = attr.value;
-    return acc;
-  }, {});
+function _hasSuspiciousNameValue(attributes) {
+  return attributes.some(attr => _isSuspiciousNameValue(attr.value));
+}
 
-  return {
-    subject,
-    issuer,
-    serialNumber: cert.serialNumber,
-    validFrom: cert.validity.notBefore,
-    validTo: cert.validity.notAfter,
-    subjectCommonName: subject.CN || 'Unknown',
-    issuerCommonName: issuer.CN || 'Unknown',
-    isCA: cert.extensions.some(ext => 
-      ext.name === 'basicConstraints' && ext.cA === true
-    ),
-    isSelfSigned: JSON.stringify(subject) === JSON.stringify(issuer),
-  };
+/**
+ * Format a distinguished name as an RFC 4514 style string (most specific first)
+ */
+function _formatDistinguishedName(attributes, oidNames = {}) {
+  return attributes
+    .map(attr => {
+      // Sanitize before RFC 4514 escaping, so the \uXXXX markers get escaped too
+      const value = _sanitizeNameValue(String(attr.value)).replace(/[,+"\\<>;]/g, '\\$&');
+      return `${oidNames[attr.type] || attr.shortName || attr.name || attr.type}=${value}`;
+    })
+    .reverse()
+    .join(', ');
 }
 
-// Main function to parse any certificate file
-export async function parseCertificateFile(file, password = null) {
-  const fileName = file.name.toLowerCase();
-  const fileExtension = fileName.split('.').pop();
-  
-  return new Promise((resolve, reject) => {
-    const reader = new FileReader();
-    
-    reader.onload = async (e) => {
-      try {
-        let result = { certificates: [], privateKeys: [], needsPassword: false };
-        
-        // Try different formats based on extension and content
-        if (fileExtension === 'pfx' || fileExtension === 'p12') {
-          try {
-            result = await parsePKCS12(e.target.result, password || '');
-          } catch (error) {
-            if (error.message === 'INVALID_PASSWORD') {
-              result.needsPassword = true;
-            } else {
-              throw error;
-            }
-          }
-        } else if (fileExtension === 'der') {
-          // DER is always binary
-          result = parseDER(e.target.result);
-        } else if (fileExtension === 'crt' || fileExtension === 'cer') {
-          // CRT/CER can be either DER or PEM
-          // We read as ArrayBuffer, try DER first
-          try {
-            result = parseDER(e.target.result);
-          } catch {
-            // If DER fails, re-read as text and try PEM
-            const textReader = new FileReader();
-            textReader.onload = (te) => {
-              result = parsePEM(te.target.result);
-              resolve(result);
-            };
-            textReader.onerror = () => reject(new Error('Failed to read file as text'));
-            textReader.readAsText(file);
-            return;
-          }
-        } else {
-          // Default to PEM (text-based) - file is already read as text
-          result = parsePEM(e.target.result);
-        }
-        
-        resolve(result);
-      } catch (error) {
-        reject(error);
-      }
+/**
+ * Every RDN attribute in order as { oid, shortName, value }; shortName is null
+ * for OIDs without a friendly name
+ */
+function _nameAttributes(attributes, oidNames = {}) {
+  return attributes.map(attr => {
+    const shortName = oidNames[attr.type] || attr.shortName || attr.name;
+    return {
+      oid: attr.type,
+      shortName: shortName && shortName !== attr.type ? shortName : null,
+      value: _sanitizeNameValue(attr.value),
     };
-    
-    reader.onerror = () => reject(new Error('Failed to read file'));
-    
-    // Read as ArrayBuffer only for binary formats
-    if (fileExtension === 'pfx' || fileExtension === 'p12' || fileExtension === 'der') {
-      reader.readAsArrayBuffer(file);
-    } else if (fileExtension === 'crt' || fileExtension === 'cer') {
-      // CRT/CER can be binary or text, try binary first
-      reader.readAsArrayBuffer(file);
-    } else {
-      // Default to text for PEM and other text-based formats
-      reader.readAsText(file);
-    }
   });
 }
 
-// Build certificate chain
-export function buildCertificateChain(certificates) {
-  const chain = [];
-  const certMap = new Map();
-  
-  // Create a map of certificates by subject
-  certificates.forEach((certWrapper, index) => {
-    const cert = certWrapper.data;
-    const info = extractCertificateInfo(cert);
-    certMap.set(index, { cert, info, wrapper: certWrapper });
-  });
+/**
+ * Format a DER Name (already decoded to ASN.1) as an RFC 4514 style string
+ */
+export function distinguishedNameFromAsn1(name) {
+  return _formatDistinguishedName(_parseRDNSequence(name));
+}
 
-  // Find leaf certificates (non-CA or end-entity certs)
-  const leaves = [];
-  certMap.forEach((value, key) => {
-    if (!value.info.isCA || value.info.isSelfSigned) {
-      leaves.push(key);
+/**
+ * Split an RFC 4514 string into RDNs at unescaped ',' and each RDN into its
+ * attribute strings at unescaped '+'
+ */
+function _splitDistinguishedName(dn) {
+  const rdns = [[]];
+  let current = '';
+  for (let i = 0; i < dn.length; i++) {
+    if (dn[i] === '\\' && i + 1 < dn.length) {
+      current += dn[i] + dn[++i];
+    } else if (dn[i] === ',' || dn[i] === '+') {
+      rdns[rdns.length - 1].push(current);
+      current = '';
+      if (dn[i] === ',') {
+        rdns.push([]);
+      }
+    } else {
+      current += dn[i];
     }
-  });
+  }
+  rdns[rdns.length - 1].push(current);
+  return rdns;
+}
 
-  // For each leaf, try to build a chain
-  leaves.forEach(leafKey => {
-    const chainForLeaf = [];
-    let current = certMap.get(leafKey);
-    const visited = new Set();
-    
-    while (current && !visited.has(current)) {
-      visited.add(current);
-      chainForLeaf.push(current);
-      
-      // Find issuer
-      if (current.info.isSelfSigned) {
-        break; // Reached root
-      }
-      
-      let found = false;
-      certMap.forEach((value) => {
-        if (!found && !visited.has(value) && 
-            value.info.subjectCommonName === current.info.issuerCommonName) {
-          current = value;
-          found = true;
-        }
-      });
-      
-      if (!found) {
-        break; // Can't find issuer
-      }
+/**
+ * Undo RFC 4514 escaping in a DN string value: "\," style escapes and runs of
+ * "\XX" hex pairs, which are UTF-8 bytes
+ */
+function _unescapeDnValue(value) {
+  return value.replace(/(?:\\[0-9a-fA-F]{2})+|\\(.)/g, (match, char) => {
+    if (char !== undefined) {
+      return char;
     }
-    
-    if (chainForLeaf.length > 0) {
-      chain.push(chainForLeaf);
+    const bytes = forge.util.hexToBytes(match.replace(/\\/g, ''));
+    try {
+      return forge.util.decodeUtf8(bytes);
+    } catch {
+      return bytes;
     }
   });
+}
 
-  return chain;
+/**
+ * A name value (Unicode text) in canonical DN form: lowercased, whitespace
+ * trimmed and collapsed, then RFC 4514 escaped
+ */
+function _canonicalDnValue(text) {
+  return text.trim().replace(/\s+/g, ' ').toLowerCase().replace(/[,+"\\<>;]/g, '\\$&');
 }
 
-// Generate nginx-ready certificate format
-export function generateNginxFormat(chain, privateKey) {
-  let output = '';
-  
-  // Add certificates in order (leaf to root)
-  chain.forEach(certInfo => {
-    output += certInfo.wrapper.pem + '\n';
+/**
+ * RDNs of a Name node as arrays of "oid=value" canonical attribute strings,
+ * most specific first. Non-string values are given as "#" and their DER in hex.
+ */
+function _canonicalRdnsFromAsn1(name) {
+  const asn1 = forge.asn1;
+  return name.value.map(rdn => rdn.value.map(attribute => {
+    const [type, value] = attribute.value;
+    const isString = value.tagClass === asn1.Class.UNIVERSAL && value.type in CANONICAL_NAME_STRING_WIDTHS;
+    const text = isString ? _nameValueUnicode(value) : `#${forge.util.bytesToHex(asn1.toDer(value).getBytes())}`;
+    return `${asn1.derToOid(type.value)}=${_canonicalDnValue(text)}`;
+  })).reverse();
+}
+
+function _canonicalRdnsFromString(dn) {
+  let text = String(dn);
+  try {
+    // Raw UTF-8 values must be decoded first, or lowercasing would mangle multi-byte characters
+    text = forge.util.decodeUtf8(text);
+  } catch {
+    // Already a Unicode string
+  }
+
+  return _splitDistinguishedName(text).map(rdn => rdn
+    .map(part => {
+      const separator = part.indexOf('=');
+      const value = _canonicalDnValue(_unescapeDnValue(part.slice(separator + 1).trim()));
+      return separator === -1 ? value : `${_nameTypeOid(part.slice(0, separator).trim())}=${value}`;
+    })
+    .filter(part => part !== ''));
+}
+
+/**
+ * Normalize a distinguished name and hash it, so names that differ only in
+ * case, spacing or how they are written compare equal. `dn` is a decoded Name
+ * ASN.1 node or an RFC 4514 string (attribute types may be short names or
+ * OIDs). Attribute types become OIDs, values are lowercased with runs of
+ * whitespace collapsed, the attributes of a multi-valued RDN are sorted and
+ * joined with '+', and RDNs are joined with ',' most specific first.
+ * Returns { canonical, sha256 } with sha256 in hex.
+ */
+export function canonicalDnHash(dn) {
+  const rdns = dn !== null && typeof dn === 'object' && Array.isArray(dn.value)
+    ? _canonicalRdnsFromAsn1(dn)
+    : _canonicalRdnsFromString(dn);
+  const canonical = rdns
+    .filter(rdn => rdn.length > 0)
+    .map(rdn => [...rdn].sort().join('+'))
+    .join(',');
+
+  const sha256 = forge.md.sha256.create().update(forge.util.encodeUtf8(canonical)).digest().toHex();
+  return { canonical, sha256 };
+}
+
+// String types OpenSSL canonicalizes in name hashes, with how their bytes map to code points
+const CANONICAL_NAME_STRING_WIDTHS = {
+  [forge.asn1.Type.UTF8]: 0,
+  [forge.asn1.Type.PRINTABLESTRING]: 1,
+  [forge.asn1.Type.T61STRING]: 1,
+  [forge.asn1.Type.IA5STRING]: 1,
+  26: 1, // VisibleString
+  28: 4, // UniversalString
+  [forge.asn1.Type.BMPSTRING]: 2,
+};
+
+/**
+ * A string name value (one of CANONICAL_NAME_STRING_WIDTHS) decoded to Unicode text
+ */
+function _nameValueUnicode(node) {
+  const width = CANONICAL_NAME_STRING_WIDTHS[node.type];
+  if (!width) {
+    return _nameValueText(node.value).text;
+  }
+  let text = '';
+  for (let i = 0; i + width <= node.value.length; i += width) {
+    let codePoint = 0;
+    for (let j = 0; j < width; j++) {
+      codePoint = codePoint * 256 + node.value.charCodeAt(i + j);
+    }
+    text += String.fromCodePoint(codePoint);
+  }
+  return text;
+}
+
+/**
+ * A name value as OpenSSL canonicalizes it: converted to UTF-8, ASCII
+ * lowercased, leading/trailing whitespace dropped and inner runs collapsed to one space
+ */
+function _canonicalNameValue(node) {
+  const width = CANONICAL_NAME_STRING_WIDTHS[node.type];
+  const utf8 = width ? forge.util.encodeUtf8(_nameValueUnicode(node)) : node.value;
+  return utf8
+    .replace(/^[ \t\n\v\f\r]+|[ \t\n\v\f\r]+$/g, '')
+    .replace(/[ \t\n\v\f\r]+/g, ' ')
+    .replace(/[A-Z]/g, c => c.toLowerCase());
+}
+
+/**
+ * OpenSSL's X509_NAME_hash (openssl x509 -subject_hash): SHA-1 over the
+ * canonical RDN SETs, whose first 4 bytes are read little-endian and shown
+ * as 8 hex digits. `name` is a raw (undecoded) Name ASN.1 node.
+ */
+function _opensslNameHash(name) {
+  const asn1 = forge.asn1;
+  const canonical = name.value.map(rdn => {
+    const attributes = rdn.value.map(attribute => {
+      const value = attribute.value[1];
+      const canonicalValue = value.tagClass === asn1.Class.UNIVERSAL && value.type in CANONICAL_NAME_STRING_WIDTHS
+        ? asn1.create(asn1.Class.UNIVERSAL, asn1.Type.UTF8, false, _canonicalNameValue(value))
+        : value;
+      const node = asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [attribute.value[0], canonicalValue]);
+      return { node, der: asn1.toDer(node).getBytes() };
+    });
+    // DER SET OF: elements sorted by their encoding
+    attributes.sort((a, b) => (a.der < b.der ? -1 : a.der > b.der ? 1 : 0));
+    return asn1.toDer(asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SET, true, attributes.map(attribute => attribute.node))).getBytes();
+  }).join('');
+
+  const digest = forge.md.sha1.create().update(canonical).digest().getBytes();
+  return [3, 2, 1, 0].map(i => digest.charCodeAt(i).toString(16).padStart(2, '0')).join('');
+}
+
+/**
+ * subjectDnHash / issuerDnHash from the encoded names' OIDs and values, so
+ * they do not depend on how the names are displayed (e.g. options.oidNames)
+ */
+function _dnHashes(cert, der) {
+  if (der) {
+    try {
+      const fields = tbsCertificateFields(der);
+      return { subjectDnHash: canonicalDnHash(fields.subject).sha256, issuerDnHash: canonicalDnHash(fields.issuer).sha256 };
+    } catch (e) {
+      console.debug('Failed to hash certificate names:', e);
+    }
+  }
+  return {
+    subjectDnHash: canonicalDnHash(_formatDistinguishedName(cert.subject.attributes)).sha256,
+    issuerDnHash: canonicalDnHash(_formatDistinguishedName(cert.issuer.attributes)).sha256,
+  };
+}
+
+/**
+ * subjectHash / issuerHash as printed by openssl x509 -subject_hash / -issuer_hash
+ */
+function _opensslNameHashes(der) {
+  if (!der) {
+    return { subjectHash: null, issuerHash: null };
+  }
+  try {
+    const fields = tbsCertificateFields(der);
+    return { subjectHash: _opensslNameHash(fields.subject), issuerHash: _opensslNameHash(fields.issuer) };
+  } catch (e) {
+    console.debug('Failed to hash certificate names:', e);
+    return { subjectHash: null, issuerHash: null };
+  }
+}
+
+/**
+ * Format an iPAddress GeneralName (4/16 bytes, or address+mask in name constraints)
+ */
+function _formatIpAddress(bytes) {
+  const octets = Array.from(bytes, c => c.charCodeAt(0));
+  const format = (part) => {
+    if (part.length === 4) {
+      return part.join('.');
+    }
+    const groups = [];
+    for (let i = 0; i + 1 < part.length; i += 2) {
+      groups.push(((part[i] << 8) | part[i + 1]).toString(16));
+    }
+    return groups.join(':');
+  };
+  if (octets.length === 8 || octets.length === 32) {
+    const half = octets.length / 2;
+    return format(octets.slice(0, half)) + '/' + format(octets.slice(half));
+  }
+  return format(octets);
+}
+
+/**
+ * Parse a single GeneralName into { type, value }
+ */
+function _parseGeneralName(node) {
+  switch (node.type) {
+    case 0:
+      return { type: 'otherName', value: forge.asn1.derToOid(node.value[0].value) };
+    case 1:
+      return { type: 'email', value: node.value };
+    case 2:
+      return { type: 'DNS', value: node.value };
+    case 4:
+      return { type: 'DirName', value: _formatDistinguishedName(_parseRDNSequence(node.value[0])) };
+    case 6:
+      return { type: 'URI', value: node.value };
+    case 7:
+      return { type: 'IP', value: _formatIpAddress(node.value) };
+    case 8:
+      return { type: 'RID', value: forge.asn1.derToOid(node.value) };
+    default:
+      return { type: 'other', value: '' };
+  }
+}
+
+/**
+ * Parse a GeneralNames SEQUENCE (SAN, issuerAltName, ...)
+ */
+function _parseGeneralNames(bytes) {
+  return fromDerRaw(bytes).value.map(_parseGeneralName);
+}
+
+/**
+ * Parse a subjectAltName (2.5.29.17) or issuerAltName (2.5.29.18) extension.
+ * Malformed certificates may repeat the extension or its entries, so names are
+ * collected from every instance and de-duplicated in first-seen order.
+ */
+function _parseAltNames(cert, oid, label) {
+  const names = [];
+  const seen = new Set();
+  for (const ext of cert.extensions.filter(extension => extension.id === oid)) {
+    try {
+      for (const name of _parseGeneralNames(ext.value)) {
+        const key = `${name.type}:${name.value}`;
+        if (!seen.has(key)) {
+          seen.add(key);
+          names.push(name);
+        }
+      }
+    } catch (e) {
+      console.debug(`Failed to parse ${label} extension:`, e);
+    }
+  }
+  return names;
+}
+
+// keyUsage bit names, in bit order (RFC 5280 section 4.2.1.3)
+export const KEY_USAGE_BITS = [
+  'digitalSignature',
+  'nonRepudiation',
+  'keyEncipherment',
+  'dataEncipherment',
+  'keyAgreement',
+  'keyCertSign',
+  'cRLSign',
+  'encipherOnly',
+  'decipherOnly',
+];
+
+// Legacy Netscape cert-type (2.16.840.1.113730.1.1) bits; bit 4 is reserved
+const NETSCAPE_CERT_TYPE_BITS = [
+  'sslClient',
+  'sslServer',
+  'smime',
+  'objectSigning',
+  null,
+  'sslCA',
+  'smimeCA',
+  'objectSigningCA',
+];
+
+/**
+ * Parse a named-bit BIT STRING extension into the names of the bits that are set
+ */
+function _parseNamedBits(cert, oid, names, label) {
+  const ext = _findExtension(cert, oid);
+  if (!ext) {
+    return [];
+  }
+  try {
+    // Raw BIT STRING: unused-bits byte, then the flags
+    const bits = fromDerRaw(ext.value).value;
+    return names.filter((name, i) => {
+      const byte = bits.charCodeAt(1 + Math.floor(i / 8));
+      return name !== null && !isNaN(byte) && (byte & (0x80 >> (i % 8))) !== 0;
+    });
+  } catch (e) {
+    console.debug(`Failed to parse ${label} extension:`, e);
+    return [];
+  }
+}
+
+/**
+ * Parse the keyUsage extension into the names of the bits that are set
+ */
+function _parseKeyUsage(cert) {
+  return _parseNamedBits(cert, '2.5.29.15', KEY_USAGE_BITS, 'keyUsage');
+}
+
+/**
+ * Whether basicConstraints and keyUsage disagree: a non-CA asserting keyCertSign,
+ * or a CA whose keyUsage extension leaves keyCertSign out (an absent keyUsage
+ * allows every use, so it never contradicts)
+ */
+function _keyUsageCaContradiction(cert, isCA, keyUsage) {
+  if (!isCA) {
+    return keyUsage.includes('keyCertSign');
+  }
+  return _findExtension(cert, '2.5.29.15') !== undefined && !keyUsage.includes('keyCertSign');
+}
+
+/**
+ * Whether basicConstraints is present but not a valid
+ * SEQUENCE { cA BOOLEAN OPTIONAL, pathLenConstraint INTEGER OPTIONAL }.
+ * An absent extension is not an error (the certificate is simply not a CA).
+ */
+function _basicConstraintsError(cert) {
+  const ext = _findExtension(cert, '2.5.29.19');
+  if (!ext) {
+    return false;
+  }
+  try {
+    const bc = fromDerRaw(ext.value);
+    if (bc.tagClass !== forge.asn1.Class.UNIVERSAL || bc.type !== forge.asn1.Type.SEQUENCE || !Array.isArray(bc.value)) {
+      return true;
+    }
+    const expected = [forge.asn1.Type.BOOLEAN, forge.asn1.Type.INTEGER];
+    let next = 0;
+    for (const node of bc.value) {
+      const position = expected.indexOf(node.type, next);
+      if (node.tagClass !== forge.asn1.Class.UNIVERSAL || position === -1) {
+        return true;
+      }
+      next = position + 1;
+    }
+    return false;
+  } catch (e) {
+    console.debug('Malformed basicConstraints extension:', e);
+    return true;
+  }
+}
+
+/**
+ * Parse the extendedKeyUsage extension into its purpose OIDs
+ */
+function _parseExtendedKeyUsage(cert) {
+  const ext = _findExtension(cert, '2.5.29.37');
+  if (!ext) {
+    return [];
+  }
+  try {
+    return fromDerRaw(ext.value).value.map(node => forge.asn1.derToOid(node.value));
+  } catch (e) {
+    console.debug('Failed to parse extendedKeyUsage extension:', e);
+    return [];
+  }
+}
+
+/**
+ * Parse the subject and authority key identifiers as hex strings.
+ * A 20-byte SKI is most likely the SHA-1 of the public key (RFC 5280 4.2.1.2);
+ * other lengths point at one of the RFC 7093 methods.
+ */
+function _parseKeyIdentifiers(cert) {
+  const ids = { subjectKeyIdentifier: null, skiLengthBytes: null, authorityKeyIdentifier: null };
+  const ski = _findExtension(cert, '2.5.29.14');
+  const aki = _findExtension(cert, '2.5.29.35');
+  try {
+    if (ski) {
+      const keyId = fromDerRaw(ski.value).value;
+      ids.subjectKeyIdentifier = forge.util.bytesToHex(keyId);
+      ids.skiLengthBytes = keyId.length;
+    }
+    if (aki) {
+      const keyId = fromDerRaw(aki.value).value.find(node =>
+        node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === 0
+      );
+      ids.authorityKeyIdentifier = keyId ? forge.util.bytesToHex(keyId.value) : null;
+    }
+  } catch (e) {
+    console.debug('Failed to parse key identifier extension:', e);
+  }
+  return ids;
+}
+
+/**
+ * Parse the authorityInfoAccess extension into OCSP and caIssuers URLs
+ */
+function _parseAuthorityInfoAccess(cert) {
+  const access = { ocspUrls: [], caIssuerUrls: [] };
+  const ext = _findExtension(cert, '1.3.6.1.5.5.7.1.1');
+  if (!ext) {
+    return access;
+  }
+  try {
+    for (const description of fromDerRaw(ext.value).value) {
+      const method = forge.asn1.derToOid(description.value[0].value);
+      const location = _parseGeneralName(description.value[1]);
+      if (location.type !== 'URI') {
+        continue;
+      }
+      if (method === '1.3.6.1.5.5.7.48.1') {
+        access.ocspUrls.push(location.value);
+      } else if (method === '1.3.6.1.5.5.7.48.2') {
+        access.caIssuerUrls.push(location.value);
+      }
+    }
+  } catch (e) {
+    console.debug('Failed to parse authorityInfoAccess extension:', e);
+  }
+  return access;
+}
+
+// Access methods seen in subjectInfoAccess (RFC 5280 4.2.2.2, RFC 6487)
+const SUBJECT_INFO_ACCESS_METHODS = {
+  '1.3.6.1.5.5.7.48.3': 'timeStamping',
+  '1.3.6.1.5.5.7.48.5': 'caRepository',
+  '1.3.6.1.5.5.7.48.10': 'rpkiManifest',
+  '1.3.6.1.5.5.7.48.11': 'signedObject',
+  '1.3.6.1.5.5.7.48.13': 'rpkiNotify',
+};
+
+/**
+ * Parse the subjectInfoAccess extension into [{ method, oid, uri }] URI entries
+ */
+function _parseSubjectInfoAccess(cert) {
+  const ext = _findExtension(cert, '1.3.6.1.5.5.7.1.11');
+  if (!ext) {
+    return [];
+  }
+  const entries = [];
+  try {
+    for (const description of fromDerRaw(ext.value).value) {
+      const oid = forge.asn1.derToOid(description.value[0].value);
+      const location = _parseGeneralName(description.value[1]);
+      if (location.type === 'URI') {
+        entries.push({ method: SUBJECT_INFO_ACCESS_METHODS[oid] || oid, oid, uri: location.value });
+      }
+    }
+  } catch (e) {
+    console.debug('Failed to parse subjectInfoAccess extension:', e);
+  }
+  return entries;
+}
+
+// Algorithms advertised in S/MIME capabilities (RFC 8551 2.5.2)
+const SMIME_CAPABILITY_NAMES = {
+  '2.16.840.1.101.3.4.1.2': 'aes128-CBC',
+  '2.16.840.1.101.3.4.1.22': 'aes192-CBC',
+  '2.16.840.1.101.3.4.1.42': 'aes256-CBC',
+  '2.16.840.1.101.3.4.1.6': 'aes128-GCM',
+  '2.16.840.1.101.3.4.1.46': 'aes256-GCM',
+  '2.16.840.1.101.3.4.1.5': 'aes128-wrap',
+  '2.16.840.1.101.3.4.1.45': 'aes256-wrap',
+  '1.2.840.113549.3.7': 'des-ede3-cbc',
+  '1.2.840.113549.3.2': 'rc2-cbc',
+  '1.3.14.3.2.7': 'des-cbc',
+  '1.2.840.113549.1.1.1': 'rsaEncryption',
+  '1.2.840.113549.1.1.7': 'RSAES-OAEP',
+  '1.2.840.113549.1.9.15.1': 'preferSignedData',
+  '1.2.840.113549.1.9.15.2': 'canNotDecryptAny',
+};
+
+/**
+ * Parse the SMIMECapabilities extension (1.2.840.113549.1.9.15) into the
+ * advertised algorithm names, in preference order (the OID when unknown)
+ */
+function _parseSmimeCapabilities(cert) {
+  const ext = _findExtension(cert, '1.2.840.113549.1.9.15');
+  if (!ext) {
+    return [];
+  }
+  try {
+    return fromDerRaw(ext.value).value.map(capability => {
+      const oid = forge.asn1.derToOid(capability.value[0].value);
+      return SMIME_CAPABILITY_NAMES[oid] || _oidName(oid) || oid;
+    });
+  } catch (e) {
+    console.debug('Failed to parse SMIMECapabilities extension:', e);
+    return [];
+  }
+}
+
+/**
+ * Parse a CRLDistributionPoints-style extension (also used by freshestCRL)
+ * into its fullName URLs
+ */
+function _parseDistributionPointUrls(cert, oid) {
+  const ext = _findExtension(cert, oid);
+  if (!ext) {
+    return [];
+  }
+  const urls = [];
+  try {
+    for (const point of fromDerRaw(ext.value).value) {
+      // distributionPoint [0] -> fullName [0] GeneralNames
+      const name = point.value.find(node => node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === 0);
+      const fullName = name && name.value.find(node => node.type === 0);
+      if (!fullName) {
+        continue;
+      }
+      fullName.value
+        .map(_parseGeneralName)
+        .filter(generalName => generalName.type === 'URI')
+        .forEach(generalName => urls.push(generalName.value));
+    }
+  } catch (e) {
+    console.debug('Failed to parse distribution points extension:', e);
+  }
+  return urls;
+}
+
+/**
+ * Parse the nameConstraints extension into permitted/excluded GeneralNames
+ */
+function _parseNameConstraints(cert) {
+  const ext = _findExtension(cert, '2.5.29.30');
+  if (!ext) {
+    return null;
+  }
+  const constraints = { permitted: [], excluded: [] };
+  try {
+    for (const subtrees of fromDerRaw(ext.value).value) {
+      const target = subtrees.type === 0 ? constraints.permitted : constraints.excluded;
+      for (const subtree of subtrees.value) {
+        target.push(_parseGeneralName(subtree.value[0]));
+      }
+    }
+  } catch (e) {
+    console.debug('Failed to parse nameConstraints extension:', e);
+  }
+  return constraints;
+}
+
+/**
+ * Decode a DisplayText (IA5String, VisibleString, BMPString or UTF8String)
+ */
+function _decodeDisplayText(node) {
+  if (node.type === forge.asn1.Type.UTF8) {
+    return forge.util.decodeUtf8(node.value);
+  }
+  if (node.type === forge.asn1.Type.BMPSTRING) {
+    let text = '';
+    for (let i = 0; i + 1 < node.value.length; i += 2) {
+      text += String.fromCharCode((node.value.charCodeAt(i) << 8) | node.value.charCodeAt(i + 1));
+    }
+    return text;
+  }
+  return node.value;
+}
+
+/**
+ * Parse CPS URLs and user notice texts from the certificatePolicies qualifiers
+ */
+function _parsePolicyQualifiers(cert) {
+  const qualifiers = { cpsUrls: [], userNotices: [] };
+  const ext = _findExtension(cert, '2.5.29.32');
+  if (!ext) {
+    return qualifiers;
+  }
+  try {
+    for (const policy of fromDerRaw(ext.value).value) {
+      const policyQualifiers = policy.value[1] ? policy.value[1].value : [];
+      for (const qualifier of policyQualifiers) {
+        const id = forge.asn1.derToOid(qualifier.value[0].value);
+        if (id === '1.3.6.1.5.5.7.2.1') {
+          qualifiers.cpsUrls.push(qualifier.value[1].value);
+        } else if (id === '1.3.6.1.5.5.7.2.2') {
+          // UserNotice: noticeRef SEQUENCE OPTIONAL, explicitText DisplayText OPTIONAL
+          const explicitText = qualifier.value[1].value.find(node => node.type !== forge.asn1.Type.SEQUENCE);
+          if (explicitText) {
+            qualifiers.userNotices.push(_decodeDisplayText(explicitText));
+          }
+        }
+      }
+    }
+  } catch (e) {
+    console.debug('Failed to parse certificatePolicies extension:', e);
+  }
+  return qualifiers;
+}
+
+const ANY_POLICY_OID = '2.5.29.32.0';
+
+/**
+ * Validation level (EV, OV, IV or DV, strongest first) asserted by the
+ * policies, or null. anyPolicy asserts none.
+ */
+function _validationLevel(policies) {
+  const levels = policies.filter(policy => CABF_POLICIES[policy.oid]).map(policy => CABF_POLICIES[policy.oid].validationLevel);
+  return ['EV', 'OV', 'IV', 'DV'].find(level => levels.includes(level)) || null;
+}
+
+/**
+ * Policy OIDs from certificatePolicies as { oid, name }
+ */
+function _parseCertificatePolicies(cert, oidNames) {
+  const ext = _findExtension(cert, '2.5.29.32');
+  if (!ext) {
+    return [];
+  }
+  try {
+    return fromDerRaw(ext.value).value.map(policy => {
+      const oid = forge.asn1.derToOid(policy.value[0].value);
+      return { oid, name: _oidName(oid, oidNames) };
+    });
+  } catch (e) {
+    console.debug('Failed to parse certificatePolicies extension:', e);
+    return [];
+  }
+}
+
+/**
+ * Collect the image URLs from the logotype extension (RFC 3709). Every
+ * logotypeURI / refStructURI is an IA5String, so the URL-shaped strings are
+ * picked out of the structure rather than walking each CHOICE.
+ */
+function _parseLogotypeUrls(cert) {
+  const ext = _findExtension(cert, '1.3.6.1.5.5.7.1.12');
+  if (!ext) {
+    return [];
+  }
+  const urls = [];
+  const collect = (node) => {
+    if (Array.isArray(node.value)) {
+      node.value.forEach(collect);
+    } else if (node.type === forge.asn1.Type.IA5STRING && /^(https?|data):/.test(node.value)) {
+      urls.push(node.value);
+    }
+  };
+  try {
+    collect(fromDerRaw(ext.value));
+  } catch (e) {
+    console.debug('Failed to parse logotype extension:', e);
+  }
+  return urls;
+}
+
+/**
+ * Parse the policy processing extensions: inhibitAnyPolicy (skipCerts),
+ * policyConstraints and policyMappings (issuer to subject policy OIDs)
+ */
+function _parsePolicyExtensions(cert) {
+  const policies = { inhibitAnyPolicy: null, policyConstraints: null, policyMappings: [] };
+  const inhibitAnyPolicy = _findExtension(cert, '2.5.29.54');
+  const policyConstraints = _findExtension(cert, '2.5.29.36');
+  const policyMappings = _findExtension(cert, '2.5.29.33');
+  try {
+    if (inhibitAnyPolicy) {
+      policies.inhibitAnyPolicy = forge.asn1.derToInteger(fromDerRaw(inhibitAnyPolicy.value).value);
+    }
+    if (policyConstraints) {
+      // requireExplicitPolicy [0] and inhibitPolicyMapping [1] are implicit SkipCerts
+      policies.policyConstraints = { requireExplicitPolicy: null, inhibitPolicyMapping: null };
+      for (const node of fromDerRaw(policyConstraints.value).value) {
+        const key = node.type === 0 ? 'requireExplicitPolicy' : 'inhibitPolicyMapping';
+        policies.policyConstraints[key] = forge.asn1.derToInteger(node.value);
+      }
+    }
+    if (policyMappings) {
+      policies.policyMappings = fromDerRaw(policyMappings.value).value.map(mapping => ({
+        issuerDomainPolicy: forge.asn1.derToOid(mapping.value[0].value),
+        subjectDomainPolicy: forge.asn1.derToOid(mapping.value[1].value),
+      }));
+    }
+  } catch (e) {
+    console.debug('Failed to parse policy extension:', e);
+  }
+  return policies;
+}
+
+/**
+ * PEM armor with 64-column base64 and LF line endings
+ */
+function _encodePem(der, label) {
+  const base64 = forge.util.encode64(der, 64).replace(/\r\n/g, '\n');
+  return `-----BEGIN ${label}-----\n` + base64 + `\n-----END ${label}-----`;
+}
+
+/**
+ * Safely convert certificate to PEM, handling non-RSA keys
+ */
+export function safeCertificateToPem(asn1) {
+  // For certificates with non-RSA keys, we need to convert the raw ASN.1 back to PEM
+  // since certificateToPem won't work with our manually parsed certificate
+  const der = forge.asn1.toDer(asn1);
+  return _encodePem(der.getBytes(), 'CERTIFICATE');
+}
+
+/**
+ * Bytes of a binary string (one byte per character) as a Uint8Array
+ */
+export function toBytes(binary) {
+  return Uint8Array.from(binary, c => c.charCodeAt(0));
+}
+
+/**
+ * Wrap DER in PEM armor. Accepts base64 text (line breaks allowed) or raw bytes
+ * (ArrayBuffer / Uint8Array). The label must be a valid RFC 7468 label such as
+ * CERTIFICATE, X509 CRL or PRIVATE KEY.
+ */
+export function toPem(base64OrDer, label = 'CERTIFICATE') {
+  if (!/^[A-Z0-9]+( [A-Z0-9]+)*$/.test(label)) {
+    throw new Error(`Invalid PEM label: ${label}`);
+  }
+
+  let der;
+  if (typeof base64OrDer === 'string') {
+    const base64 = base64OrDer.replace(/\s/g, '');
+    if (!base64 || !/^[A-Za-z0-9+/]+={0,2}$/.test(base64) || base64.length % 4 !== 0) {
+      throw new Error('Input is not valid base64');
+    }
+    der = forge.util.decode64(base64);
+  } else {
+    der = forge.util.createBuffer(base64OrDer).getBytes();
+  }
+
+  return _encodePem(der, label);
+}
+
+/**
+ * Return the base64 DER of the first PEM block as a single line
+ */
+export function toSingleLineDer(pem) {
+  const match = pem.match(/-----BEGIN ([A-Z0-9 ]+)-----([\s\S]*?)-----END \1-----/);
+  if (!match) {
+    throw new Error('No PEM block found');
+  }
+  return match[2].replace(/\s/g, '');
+}
+
+/**
+ * DER bytes of a single certificate PEM (for .der/.cer downloads); toPem
+ * converts back. Input with several certificates is rejected.
+ */
+export function certToDer(pem) {
+  const count = (pem.match(/-----BEGIN (X509 |TRUSTED )?CERTIFICATE-----/g) || []).length;
+  if (count > 1) {
+    throw new Error(`Input contains ${count} certificates; pass the one to export`);
+  }
+  return chainToDer([pem]);
+}
+
+/**
+ * Concatenate the DER of each certificate PEM, in order, into one Uint8Array
+ * (the form some protocols expect for a chain). parseDER reads it back.
+ */
+export function chainToDer(chainPems) {
+  return toBytes(chainPems.map(pem => certificateFromPem(pem).der).join(''));
+}
+
+/**
+ * Cheap check for "is this a certificate at all": a PEM CERTIFICATE marker, or
+ * a DER SEQUENCE whose length fits the input and which starts with another
+ * SEQUENCE (the TBSCertificate). Nothing is parsed beyond the outer header.
+ */
+export function looksLikeCertificate(bytes) {
+  const data = typeof bytes === 'string' ? bytes : forge.util.createBuffer(bytes).getBytes();
+  if (/-----BEGIN (X509 |TRUSTED )?CERTIFICATE-----/.test(data)) {
+    return true;
+  }
+
+  if (data.charCodeAt(0) !== 0x30) {
+    return false;
+  }
+  const lengthByte = data.charCodeAt(1);
+  let length = lengthByte;
+  let headerLength = 2;
+  if (lengthByte & 0x80) {
+    const lengthBytes = lengthByte & 0x7f;
+    if (lengthBytes < 1 || lengthBytes > 4) {
+      return false;
+    }
+    length = 0;
+    for (let i = 0; i < lengthBytes; i++) {
+      length = length * 256 + data.charCodeAt(2 + i);
+    }
+    headerLength += lengthBytes;
+  }
+  return headerLength + length <= data.length && data.charCodeAt(headerLength) === 0x30;
+}
+
+/**
+ * Parse a single PEM-encoded certificate, handling non-RSA keys.
+ * The decoded DER is kept on the returned certificate as `der`.
+ */
+export function certificateFromPem(pem, options = {}) {
+  checkInputSize(pem, options.maxInputBytes);
+  checkNotEmpty(pem);
+  const pemContent = pem
+    .replace(/-----BEGIN CERTIFICATE-----/, '')
+    .replace(/-----END CERTIFICATE-----/, '')
+    .replace(/\s/g, '');
+  const der = forge.util.decode64(pemContent);
+  const cert = safeCertificateFromAsn1(forge.asn1.fromDer(der));
+  cert.der = der;
+  return cert;
+}
+
+/**
+ * Decrypt an ENCRYPTED PRIVATE KEY (PKCS#8, e.g. PBES2) block to an
+ * unencrypted PKCS#8 PEM, or null if the password is wrong
+ */
+function _decryptPrivateKeyPem(pem, password) {
+  const der = forge.util.decode64(pem.replace(/-----(BEGIN|END) ENCRYPTED PRIVATE KEY-----/g, '').replace(/\s/g, ''));
+  const privateKeyInfo = forge.pki.decryptPrivateKeyInfo(forge.asn1.fromDer(der), password);
+  return privateKeyInfo ? _encodePem(forge.asn1.toDer(privateKeyInfo).getBytes(), 'PRIVATE KEY') : null;
+}
+
+// Try to parse PEM format.
+// With a password, encrypted PKCS#8 keys are decrypted; each encrypted key is
+// marked decrypted: true or false (null when no password was given).
+function parsePEM(data, password = null) {
+  const certificates = [];
+  const privateKeys = [];
+  // Blocks that are neither certificates nor keys (e.g. DH PARAMETERS)
+  const others = [];
+  // Canonical DER of the certificates so far: the same certificate pasted twice
+  // with different wrapping or whitespace is only kept once
+  const seenDer = new Set();
+  
+  try {
+    // Try to parse as PEM
+    const lines = data.split('\n');
+    let currentBlock = [];
+    let inBlock = false;
+    let blockType = null;
+
+    for (const line of lines) {
+      const trimmed = line.trim();
+      
+      if (trimmed.startsWith('-----BEGIN')) {
+        inBlock = true;
+        blockType = trimmed;
+        currentBlock = [line];
+      } else if (trimmed.startsWith('-----END')) {
+        currentBlock.push(line);
+        inBlock = false;
+        
+        const pemBlock = currentBlock.join('\n');
+        
+        if (blockType.includes('CERTIFICATE')) {
+          try {
+            const cert = certificateFromPem(pemBlock);
+            
+            if (!seenDer.has(cert.der)) {
+              seenDer.add(cert.der);
+              certificates.push({
+                type: 'certificate',
+                data: cert,
+                // Re-wrap so single-line or oddly wrapped input is emitted as standard PEM
+                pem: _encodePem(cert.der, 'CERTIFICATE'),
+              });
+            }
+          } catch (e) {
+            console.warn('Failed to parse certificate:', e);
+          }
+        } else if (blockType.includes('PRIVATE KEY') || blockType.includes('RSA PRIVATE KEY')) {
+          const encrypted = blockType.includes('ENCRYPTED');
+          let decryptedPem = null;
+          if (encrypted && password !== null && blockType.includes('ENCRYPTED PRIVATE KEY')) {
+            try {
+              decryptedPem = _decryptPrivateKeyPem(pemBlock, password);
+            } catch (e) {
+              console.warn('Failed to decrypt private key:', e);
+            }
+          }
+          privateKeys.push({
+            type: 'privateKey',
+            pem: decryptedPem || pemBlock,
+            encrypted: encrypted && !decryptedPem,
+            decrypted: encrypted && password !== null ? decryptedPem !== null : null,
+          });
+        } else {
+          others.push({
+            type: 'other',
+            pemType: blockType.replace(/^-----BEGIN /, '').replace(/-----$/, ''),
+            pem: pemBlock,
+          });
+        }
+        
+        currentBlock = [];
+        blockType = null;
+      } else if (inBlock) {
+        currentBlock.push(line);
+      }
+    }
+  } catch (e) {
+    console.error('Error parsing PEM:', e);
+  }
+
+  return { certificates, privateKeys, others };
+}
+
+/**
+ * Attribute types commonly carried in attribute certificates (RFC 5755)
+ */
+const ATTRIBUTE_CERTIFICATE_ATTRIBUTES = {
+  '1.3.6.1.5.5.7.10.1': 'authenticationInfo',
+  '1.3.6.1.5.5.7.10.2': 'accessIdentity',
+  '1.3.6.1.5.5.7.10.3': 'chargingIdentity',
+  '1.3.6.1.5.5.7.10.4': 'group',
+  '2.5.4.72': 'role',
+  '2.5.4.55': 'clearance',
+};
+
+/**
+ * Detect an AttributeCertificate: its info starts with a bare version INTEGER
+ * and has a GeneralizedTime validity period where a certificate has its subject
+ */
+function _isAttributeCertificate(asn1) {
+  const info = asn1.value[0];
+  if (!info || !Array.isArray(info.value) || info.value.length < 7) {
+    return false;
+  }
+  const [version, , , , , validity] = info.value;
+  return version.tagClass === forge.asn1.Class.UNIVERSAL &&
+    version.type === forge.asn1.Type.INTEGER &&
+    Array.isArray(validity.value) &&
+    validity.value.length === 2 &&
+    validity.value.every(node => node.type === forge.asn1.Type.GENERALIZEDTIME);
+}
+
+/**
+ * Parse an IssuerSerial (issuer GeneralNames + serial) from a holder or issuer
+ */
+function _parseIssuerSerial(node) {
+  return {
+    issuer: node.value[0].value.map(_parseGeneralName),
+    serialNumber: unsignedHex(node.value[1].value),
+  };
+}
+
+/**
+ * Describe a single attribute value as text where the syntax is known
+ */
+function _describeAttributeValue(oid, node) {
+  switch (node.type) {
+    case forge.asn1.Type.UTF8:
+      return forge.util.decodeUtf8(node.value);
+    case forge.asn1.Type.PRINTABLESTRING:
+    case forge.asn1.Type.IA5STRING:
+      return node.value;
+    case forge.asn1.Type.OID:
+      return forge.asn1.derToOid(node.value);
+  }
+
+  if (oid === '2.5.4.72') {
+    // RoleSyntax: roleAuthority [0] OPTIONAL, roleName [1] GeneralName
+    const roleName = node.value.find(child => child.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && child.type === 1);
+    if (roleName) {
+      return _parseGeneralName(roleName.value[0]).value;
+    }
+  } else if (['1.3.6.1.5.5.7.10.3', '1.3.6.1.5.5.7.10.4'].includes(oid)) {
+    // IetfAttrSyntax: policyAuthority [0] OPTIONAL, values SEQUENCE OF (octets | oid | string)
+    const values = node.value.find(child => child.tagClass === forge.asn1.Class.UNIVERSAL);
+    if (values) {
+      return values.value.map(value => _describeAttributeValue(null, value)).join(', ');
+    }
+  }
+
+  return forge.util.bytesToHex(forge.asn1.toDer(node).getBytes());
+}
+
+/**
+ * Parse a DER attribute certificate (RFC 5755) into its holder, issuer,
+ * validity period and attributes
+ */
+export function parseAttributeCertificate(der, options = {}) {
+  checkInputSize(der, options.maxInputBytes);
+  checkNotEmpty(der);
+  return _parseAttributeCertificate(der);
+}
+
+function _parseAttributeCertificate(der) {
+  const bytes = typeof der === 'string' ? der : forge.util.createBuffer(der).getBytes();
+  const asn1 = fromDerRaw(bytes);
+  if (!_isAttributeCertificate(asn1)) {
+    throw new Error('Not an attribute certificate');
+  }
+
+  const [, holderNode, issuerNode, signature, serialNumber, validity, attributes] = asn1.value[0].value;
+
+  const holder = { baseCertificateId: null, entityName: [] };
+  for (const node of holderNode.value) {
+    if (node.type === 0) {
+      holder.baseCertificateId = _parseIssuerSerial(node);
+    } else if (node.type === 1) {
+      holder.entityName = node.value.map(_parseGeneralName);
+    }
+  }
+
+  // AttCertIssuer is either v1Form GeneralNames or v2Form [0] with issuerName first
+  let issuer = [];
+  if (issuerNode.tagClass === forge.asn1.Class.UNIVERSAL) {
+    issuer = issuerNode.value.map(_parseGeneralName);
+  } else {
+    const issuerName = issuerNode.value.find(node => node.tagClass === forge.asn1.Class.UNIVERSAL);
+    issuer = issuerName ? issuerName.value.map(_parseGeneralName) : [];
+  }
+
+  const signatureOid = forge.asn1.derToOid(signature.value[0].value);
+  const signatureAlgorithm = SIGNATURE_ALGORITHMS[signatureOid];
+
+  return {
+    type: 'attributeCertificate',
+    holder,
+    issuer,
+    serialNumber: unsignedHex(serialNumber.value),
+    validFrom: parseTime(validity.value[0]),
+    validTo: parseTime(validity.value[1]),
+    signatureAlgorithm: signatureAlgorithm ? signatureAlgorithm.name : signatureOid,
+    attributes: attributes.value.map(attribute => {
+      const oid = forge.asn1.derToOid(attribute.value[0].value);
+      return {
+        oid,
+        name: ATTRIBUTE_CERTIFICATE_ATTRIBUTES[oid] || forge.pki.oids[oid] || oid,
+        values: attribute.value[1].value.map(value => _describeAttributeValue(oid, value)),
+      };
+    }),
+  };
+}
+
+/**
+ * How many bytes a certificate starting at `offset` is missing, or 0 when it
+ * is complete or does not look like one (a SEQUENCE with a long-form length
+ * directly wrapping another SEQUENCE, the TBSCertificate)
+ */
+function _derShortfall(bytes, offset) {
+  const lengthByte = bytes.charCodeAt(offset + 1);
+  const lengthBytes = lengthByte & 0x7f;
+  if (bytes.charCodeAt(offset) !== 0x30 || !(lengthByte & 0x80) || lengthBytes < 1 || lengthBytes > 4) {
+    return 0;
+  }
+  const headerEnd = offset + 2 + lengthBytes;
+  if (headerEnd >= bytes.length || bytes.charCodeAt(headerEnd) !== 0x30) {
+    return 0;
+  }
+  let length = 0;
+  for (let i = offset + 2; i < headerEnd; i++) {
+    length = length * 256 + bytes.charCodeAt(i);
+  }
+  return Math.max(0, headerEnd + length - bytes.length);
+}
+
+// Try to parse DER format: one certificate, or several concatenated.
+// A certificate cut off mid-transfer throws a TRUNCATED_INPUT CertificateError.
+function parseDER(arrayBuffer) {
+  const certificates = [];
+  const bytes = forge.util.createBuffer(arrayBuffer).getBytes();
+  let offset = 0;
+  
+  try {
+    const buffer = forge.util.createBuffer(bytes);
+    while (buffer.length() > 0) {
+      offset = bytes.length - buffer.length();
+      const asn1 = forge.asn1.fromDer(buffer, { parseAllBytes: false });
+      if (certificates.length === 0 && buffer.length() === 0 && _isAttributeCertificate(asn1)) {
+        return { certificates, privateKeys: [], attributeCertificates: [_parseAttributeCertificate(arrayBuffer)] };
+      }
+      const cert = safeCertificateFromAsn1(asn1);
+      cert.der = forge.asn1.toDer(asn1).getBytes();
+      const pem = safeCertificateToPem(asn1);
+
+      certificates.push({
+        type: 'certificate',
+        data: cert,
+        pem: pem,
+      });
+    }
+  } catch (e) {
+    const missing = _derShortfall(bytes, offset);
+    if (missing > 0) {
+      throw new CertificateError(
+        CertificateErrorCode.TRUNCATED_INPUT,
+        `Truncated certificate (expected ${missing} more bytes)`
+      );
+    }
+    console.warn('Not a DER certificate:', e);
+    certificates.length = 0;
+  }
+
+  return { certificates, privateKeys: [] };
+}
+
+function _uint(data, offset, length) {
+  if (offset + length > data.length) {
+    throw new Error('Truncated TLS Certificate message');
+  }
+  let value = 0;
+  for (let i = 0; i < length; i++) {
+    value = value * 256 + data.charCodeAt(offset + i);
+  }
+  return value;
+}
+
+/**
+ * Parse the certificate list of a TLS Certificate handshake message. TLS 1.3
+ * entries carry a 2-byte length-prefixed extensions block after each certificate.
+ */
+function _tlsCertificateList(data, offset, withExtensions) {
+  const certificates = [];
+  while (offset < data.length) {
+    const length = _uint(data, offset, 3);
+    const der = data.substring(offset + 3, offset + 3 + length);
+    if (der.length !== length) {
+      throw new Error('Truncated TLS Certificate message');
+    }
+    offset += 3 + length;
+    if (withExtensions) {
+      offset += 2 + _uint(data, offset, 2);
+    }
+
+    const asn1 = forge.asn1.fromDer(der);
+    const cert = safeCertificateFromAsn1(asn1);
+    cert.der = der;
+    certificates.push({ type: 'certificate', data: cert, pem: _encodePem(der, 'CERTIFICATE') });
+  }
+  if (offset !== data.length) {
+    throw new Error('Truncated TLS Certificate message');
+  }
+  return certificates;
+}
+
+/**
+ * Parse the certificates out of a TLS 1.2 or 1.3 Certificate handshake message,
+ * with or without its 4-byte handshake header. Accepts bytes (ArrayBuffer /
+ * Uint8Array) or pasted hex or base64 text.
+ * Returns { version: '1.2' | '1.3', certificates } in the order sent.
+ */
+export function parseTlsCertificateMessage(bytes, options = {}) {
+  checkInputSize(bytes, options.maxInputBytes);
+  checkNotEmpty(bytes);
+  let data;
+  if (typeof bytes === 'string') {
+    const text = bytes.replace(/[\s:]/g, '');
+    data = /^([0-9a-f]{2})+$/i.test(text) ? forge.util.hexToBytes(text) : forge.util.decode64(text);
+  } else {
+    data = forge.util.createBuffer(bytes).getBytes();
+  }
+
+  // Handshake header: msg_type certificate(11) and a 3-byte length
+  if (data.length >= 4 && data.charCodeAt(0) === 11 && _uint(data, 1, 3) === data.length - 4) {
+    data = data.substring(4);
+  }
+
+  // TLS 1.2: certificate_list<0..2^24-1>
+  if (data.length >= 3 && _uint(data, 0, 3) === data.length - 3) {
+    return { version: '1.2', certificates: _tlsCertificateList(data, 3, false) };
+  }
+
+  // TLS 1.3: certificate_request_context<0..255>, then certificate_list
+  const contextLength = data.length > 0 ? data.charCodeAt(0) : 0;
+  const listOffset = 1 + contextLength + 3;
+  if (data.length >= listOffset && _uint(data, 1 + contextLength, 3) === data.length - listOffset) {
+    return { version: '1.3', certificates: _tlsCertificateList(data, listOffset, true) };
+  }
+
+  throw new Error('Not a TLS Certificate message');
+}
+
+// Parse a file whose extension says nothing about its encoding: PEM text first,
+// then DER (one certificate or several concatenated, as some Windows tools export)
+function parsePemOrDer(arrayBuffer, password = null) {
+  const bytes = forge.util.createBuffer(arrayBuffer).getBytes();
+  let text;
+  try {
+    text = forge.util.decodeUtf8(bytes);
+  } catch {
+    text = bytes;
+  }
+  if (text.includes('-----BEGIN')) {
+    return parsePEM(text, password);
+  }
+
+  const result = parseDER(bytes);
+  if (result.certificates.length > 0 || result.attributeCertificates) {
+    return result;
+  }
+  return { certificates: [], privateKeys: [], others: [] };
+}
+
+/**
+ * Parse a JSON array of certificates, as some APIs return chains: each entry
+ * is base64 DER (standard or URL-safe) or a PEM string. Certificates are
+ * returned in array order; an entry that holds no certificate is an error.
+ */
+export function parseJsonCertArray(json, options = {}) {
+  checkInputSize(json, options.maxInputBytes);
+  checkNotEmpty(json);
+  const entries = JSON.parse(json);
+  if (!Array.isArray(entries)) {
+    throw new Error('Expected a JSON array of certificates');
+  }
+
+  const certificates = entries.flatMap((entry, index) => {
+    if (typeof entry !== 'string') {
+      throw new Error(`Entry ${index} is not a string`);
+    }
+    const parsed = entry.includes('-----BEGIN')
+      ? parsePEM(entry)
+      : parseDER(forge.util.decode64(entry.replace(/\s/g, '').replace(/-/g, '+').replace(/_/g, '/')));
+    if (parsed.certificates.length === 0) {
+      throw new Error(`Entry ${index} is not a certificate`);
+    }
+    return parsed.certificates;
+  });
+  return { certificates, privateKeys: [], others: [] };
+}
+
+// Parse a .crt/.cer file: DER first, then PEM text, then bare base64 DER
+// without armor (as exported by some Windows tools)
+function parseCrtOrCer(arrayBuffer) {
+  const result = parseDER(arrayBuffer);
+  if (result.certificates.length > 0 || result.attributeCertificates) {
+    return result;
+  }
+
+  const text = forge.util.createBuffer(arrayBuffer).getBytes();
+  if (text.includes('-----BEGIN')) {
+    return parsePEM(text);
+  }
+
+  const base64 = text.replace(/\s/g, '');
+  if (/^[A-Za-z0-9+/]+={0,2}$/.test(base64) && base64.length % 4 === 0) {
+    return parseDER(forge.util.decode64(base64));
+  }
+
+  return result;
+}
+
+/**
+ * The friendlyName and localKeyID (hex) attributes of a PKCS#12 bag
+ */
+function _bagAttributes(bag) {
+  const friendlyName = bag.attributes.friendlyName;
+  const localKeyId = bag.attributes.localKeyId;
+  return {
+    friendlyName: friendlyName && friendlyName.length > 0 ? friendlyName[0] : null,
+    localKeyId: localKeyId && localKeyId.length > 0 ? forge.util.bytesToHex(localKeyId[0]) : null,
+  };
+}
+
+// Password-based encryption schemes considered insecure for PKCS#12 exports:
+// 40-bit RC2/RC4, RC4 and single-DES PBES1 (common in old Windows/OpenSSL exports)
+const WEAK_PKCS12_PBE_OIDS = new Set([
+  '1.2.840.113549.1.12.1.1', // pbeWithSHAAnd128BitRC4
+  '1.2.840.113549.1.12.1.2', // pbeWithSHAAnd40BitRC4
+  '1.2.840.113549.1.12.1.6', // pbeWithSHAAnd40BitRC2-CBC
+  '1.2.840.113549.1.5.1', // pbeWithMD2AndDES-CBC
+  '1.2.840.113549.1.5.3', // pbeWithMD5AndDES-CBC
+  '1.2.840.113549.1.5.10', // pbeWithSHA1AndDES-CBC
+]);
+
+function _octetStringBytes(node) {
+  return node.constructed ? node.value.map(child => child.value).join('') : node.value;
+}
+
+/**
+ * Encryption algorithm OIDs used by a PFX, read without decrypting it: those
+ * of encrypted safe contents and of shrouded key bags in plain safe contents
+ */
+function _pkcs12EncryptionOids(p12Asn1) {
+  const oids = [];
+  try {
+    const authSafe = fromDerRaw(_octetStringBytes(p12Asn1.value[1].value[1].value[0]));
+    for (const contentInfo of authSafe.value) {
+      const contentType = forge.asn1.derToOid(contentInfo.value[0].value);
+      const content = contentInfo.value[1].value[0];
+      if (contentType === '1.2.840.113549.1.7.6') {
+        // EncryptedData: version, EncryptedContentInfo { contentType, algorithm, ... }
+        oids.push(forge.asn1.derToOid(content.value[1].value[1].value[0].value));
+      } else if (contentType === '1.2.840.113549.1.7.1') {
+        for (const safeBag of fromDerRaw(_octetStringBytes(content)).value) {
+          if (forge.asn1.derToOid(safeBag.value[0].value) === forge.pki.oids.pkcs8ShroudedKeyBag) {
+            oids.push(forge.asn1.derToOid(safeBag.value[1].value[0].value[0].value[0].value));
+          }
+        }
+      }
+    }
+  } catch (e) {
+    console.debug('Failed to read PKCS#12 encryption algorithms:', e);
+  }
+  return oids;
+}
+
+// Try to parse PKCS#12/PFX format
+async function parsePKCS12(arrayBuffer, password = '') {
+  const certificates = [];
+  const privateKeys = [];
+  let pkcs12WeakEncryption = false;
+  
+  try {
+    const p12Der = forge.util.createBuffer(arrayBuffer);
+    const p12Asn1 = forge.asn1.fromDer(p12Der);
+    pkcs12WeakEncryption = _pkcs12EncryptionOids(p12Asn1).some(oid => WEAK_PKCS12_PBE_OIDS.has(oid));
+    const p12 = forge.pkcs12.pkcs12FromAsn1(p12Asn1, password);
+
+    // Extract certificates
+    const certBags = p12.getBags({ bagType: forge.pki.oids.certBag });
+    for (const bagType in certBags) {
+      for (const bag of certBags[bagType]) {
+        if (bag.cert) {
+          try {
+            // Try standard PEM conversion
+            const pem = forge.pki.certificateToPem(bag.cert);
+            certificates.push({
+              type: 'certificate',
+              data: bag.cert,
+              pem: pem,
+              bagAttributes: _bagAttributes(bag),
+            });
+          } catch (e) {
+            console.warn('Failed to convert PKCS#12 certificate to PEM:', e);
+          }
+        } else if (bag.asn1) {
+          // node-forge leaves non-RSA certificates undecoded; convert from ASN.1
+          const cert = safeCertificateFromAsn1(bag.asn1);
+          cert.der = forge.asn1.toDer(bag.asn1).getBytes();
+          certificates.push({
+            type: 'certificate',
+            data: cert,
+            pem: safeCertificateToPem(bag.asn1),
+            bagAttributes: _bagAttributes(bag),
+          });
+        }
+      }
+    }
+
+    // Extract private keys, shrouded and unencrypted
+    for (const keyBagType of [forge.pki.oids.pkcs8ShroudedKeyBag, forge.pki.oids.keyBag]) {
+      const keyBags = p12.getBags({ bagType: keyBagType });
+      for (const bagType in keyBags) {
+        for (const bag of keyBags[bagType]) {
+          // node-forge only decodes RSA keys; others are left as PKCS#8 ASN.1
+          let pem = null;
+          if (bag.key) {
+            pem = forge.pki.privateKeyToPem(bag.key);
+          } else if (bag.asn1) {
+            pem = _encodePem(forge.asn1.toDer(bag.asn1).getBytes(), 'PRIVATE KEY');
+          }
+          if (pem) {
+            const bagAttributes = _bagAttributes(bag);
+            privateKeys.push({
+              type: 'privateKey',
+              pem: pem,
+              encrypted: false,
+              bagAttributes,
+              // A localKeyID shared with a certificate bag ties the key to that certificate
+              boundToCertificate: bagAttributes.localKeyId !== null && certificates.some(certWrapper =>
+                certWrapper.bagAttributes.localKeyId === bagAttributes.localKeyId
+              ),
+            });
+          }
+        }
+      }
+    }
+  } catch (e) {
+    if (e.message && e.message.includes('Invalid password')) {
+      throw new Error('INVALID_PASSWORD');
+    }
+    throw e;
+  }
+
+  return { certificates, privateKeys, pkcs12WeakEncryption };
+}
+
+/**
+ * OpenSSL-style "Bag Attributes" comment lines, as printed by `openssl pkcs12 -info`
+ */
+function _bagAttributeLines(bagAttributes) {
+  const lines = [];
+  if (bagAttributes && bagAttributes.localKeyId) {
+    lines.push(`    localKeyID: ${bagAttributes.localKeyId.toUpperCase().match(/../g).join(' ')}`);
+  }
+  if (bagAttributes && bagAttributes.friendlyName) {
+    lines.push(`    friendlyName: ${bagAttributes.friendlyName}`);
+  }
+  return lines.length > 0 ? 'Bag Attributes\n' + lines.join('\n') + '\n' : 'Bag Attributes: <No Attributes>\n';
+}
+
+function _opensslName(attributes) {
+  return attributes.map(attr => `${attr.shortName || attr.type} = ${attr.value}`).join(', ');
+}
+
+function _certificatePemWithBagAttributes(certWrapper) {
+  const cert = certWrapper.data;
+  return _bagAttributeLines(certWrapper.bagAttributes) +
+    `subject=${_opensslName(cert.subject.attributes)}\n` +
+    `issuer=${_opensslName(cert.issuer.attributes)}\n` +
+    certWrapper.pem;
+}
+
+/**
+ * Decrypt a PKCS#12 file into PEMs ready for the server-format generators:
+ * { leaf, chain: [intermediate PEMs, leaf's issuer first], key, others }. The
+ * leaf is the certificate matching the private key, falling back to the
+ * chain's leaf. `others` holds the remaining certificates (the root, and any
+ * not on the leaf's chain) in file order.
+ *
+ * Pass { bagAttributes: true } to prefix each block with the OpenSSL-style
+ * "Bag Attributes" lines (friendlyName, localKeyID); parsePEM skips them.
+ */
+export async function pkcs12ToPem(bytes, password, options = {}) {
+  checkInputSize(bytes, options.maxInputBytes);
+  checkNotEmpty(bytes);
+  const { certificates, privateKeys } = await parsePKCS12(bytes, password);
+  const key = privateKeys.length > 0 ? privateKeys[0].pem.trim() : null;
+  const pems = certificates.map(certWrapper => certWrapper.pem.trim());
+  const wrappers = _wrapCertificatePems(pems).map((certWrapper, index) => ({
+    ...certWrapper,
+    bagAttributes: certificates[index].bagAttributes,
+  }));
+
+  const chains = buildCertificateChain(wrappers);
+  const ownsKey = (chain) => {
+    try {
+      return key !== null && keyMatchesCert(chain[0].wrapper.pem, key);
+    } catch (e) {
+      return false;
+    }
+  };
+  const chain = chains.find(ownsKey) ||
+    chains.reduce((longest, candidate) => (candidate.length > longest.length ? candidate : longest), []);
+  if (chain.length === 0) {
+    throw new Error('PKCS#12 file contains no certificates');
+  }
+
+  const intermediates = chain.slice(1).filter(certInfo => !certInfo.info.isSelfSigned);
+  const used = new Set([chain[0], ...intermediates].map(certInfo => certInfo.wrapper));
+  const others = wrappers.filter(certWrapper => !used.has(certWrapper));
+  if (options.bagAttributes) {
+    return {
+      leaf: _certificatePemWithBagAttributes(chain[0].wrapper),
+      chain: intermediates.map(certInfo => _certificatePemWithBagAttributes(certInfo.wrapper)),
+      key: key && _bagAttributeLines(privateKeys[0].bagAttributes) + 'Key Attributes: <No Attributes>\n' + key,
+      others: others.map(_certificatePemWithBagAttributes),
+    };
+  }
+
+  return {
+    leaf: chain[0].wrapper.pem,
+    chain: intermediates.map(certInfo => certInfo.wrapper.pem),
+    key,
+    others: others.map(certWrapper => certWrapper.pem),
+  };
+}
+
+/**
+ * Compare dotted OIDs arc by arc
+ */
+function _compareOids(a, b) {
+  const arcsA = a.split('.').map(Number);
+  const arcsB = b.split('.').map(Number);
+  for (let i = 0; i < Math.min(arcsA.length, arcsB.length); i++) {
+    if (arcsA[i] !== arcsB[i]) {
+      return arcsA[i] - arcsB[i];
+    }
+  }
+  return arcsA.length - arcsB.length;
+}
+
+// Longest extension value (in bytes) shown in full as valueHex; extensionValueHex has the rest
+const EXTENSION_VALUE_HEX_LIMIT = 128;
+
+/**
+ * List extensions in ASN.1 order, or sorted by OID when `stable` is set.
+ * Extensions without a name also carry their raw value as valueHex, cut to
+ * EXTENSION_VALUE_HEX_LIMIT bytes with a trailing ellipsis; known ones have null.
+ */
+function _listExtensions(cert, stable) {
+  const extensions = cert.extensions.map(ext => {
+    const unknown = !ext.name || ext.name === ext.id;
+    const value = unknown ? ext.value || '' : null;
+    return {
+      oid: ext.id,
+      name: ext.name || ext.id,
+      critical: ext.critical,
+      valueHex: value === null ? null : forge.util.bytesToHex(value.substring(0, EXTENSION_VALUE_HEX_LIMIT)) +
+        (value.length > EXTENSION_VALUE_HEX_LIMIT ? '…' : ''),
+    };
+  });
+  return stable ? extensions.sort((a, b) => _compareOids(a.oid, b.oid)) : extensions;
+}
+
+/**
+ * DER bytes of a certificate: kept from parsing, or re-encoded for forge certificates
+ */
+function _certificateDer(cert) {
+  if (cert.der) {
+    return cert.der;
+  }
+  try {
+    return forge.asn1.toDer(forge.pki.certificateToAsn1(cert)).getBytes();
+  } catch (e) {
+    console.debug('Failed to encode certificate to DER:', e);
+    return null;
+  }
+}
+
+/**
+ * Raw signature value and, for ECDSA, its r and s integers as hex
+ */
+function _parseSignatureValue(der) {
+  const value = { signatureValueHex: null, signatureBits: null, signatureR: null, signatureS: null };
+  if (!der) {
+    return value;
+  }
+  try {
+    const certAsn1 = fromDerRaw(der);
+    const algorithmOid = forge.asn1.derToOid(certAsn1.value[1].value[0].value);
+    const bitString = certAsn1.value[2].value;
+    const signature = bitString.substring(1);
+
+    value.signatureValueHex = forge.util.bytesToHex(signature);
+    value.signatureBits = signature.length * 8 - bitString.charCodeAt(0);
+
+    if (algorithmOid.startsWith('1.2.840.10045.4.')) {
+      // ECDSA-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+      const ecdsaSignature = fromDerRaw(signature);
+      value.signatureR = unsignedHex(ecdsaSignature.value[0].value);
+      value.signatureS = unsignedHex(ecdsaSignature.value[1].value);
+    }
+  } catch (e) {
+    console.debug('Failed to parse signature value:', e);
+  }
+  return value;
+}
+
+function _fingerprint(md, bytes) {
+  const hex = md.create().update(bytes).digest().toHex().toUpperCase();
+  return hex.match(/.{2}/g).join(':');
+}
+
+/**
+ * Certificate fingerprints (colon-separated hex, as shown by browsers and
+ * OpenSSL) and the base64 SHA-256 SPKI pin used for key pinning
+ */
+function _fingerprints(der) {
+  if (!der) {
+    return { sha256Fingerprint: null, sha1Fingerprint: null, spkiSha256Pin: null };
+  }
+  const spki = forge.asn1.toDer(tbsCertificateFields(der).subjectPublicKeyInfo).getBytes();
+  return {
+    sha256Fingerprint: _fingerprint(forge.md.sha256, der),
+    sha1Fingerprint: _fingerprint(forge.md.sha1, der),
+    spkiSha256Pin: forge.util.encode64(forge.md.sha256.create().update(spki).digest().getBytes()),
+  };
+}
+
+/**
+ * SHA-1, SHA-256 and MD5 fingerprints plus the SPKI pin for each certificate
+ * PEM, in order (for inventory tooling that indexes certificates by hash)
+ */
+export function fingerprintBundle(pems) {
+  return pems.map(pem => {
+    const der = certificateFromPem(pem).der;
+    return { ..._fingerprints(der), md5Fingerprint: _fingerprint(forge.md.md5, der) };
+  });
+}
+
+/**
+ * Size of the PEM encoding: 64-column base64 plus BEGIN/END lines, LF line endings
+ */
+function _pemSize(derLength) {
+  const base64Length = 4 * Math.ceil(derLength / 3);
+  const lines = Math.ceil(base64Length / 64);
+  return '-----BEGIN CERTIFICATE-----\n'.length + base64Length + lines + '-----END CERTIFICATE-----\n'.length;
+}
+
+/**
+ * Everything the certificate covers: the CN (if it looks like a hostname) and
+ * all dNSName SANs, lowercased and de-duplicated
+ */
+function _coveredNames(commonName, dnsNames) {
+  const names = (_looksLikeHostname(commonName) ? [commonName, ...dnsNames] : dnsNames).map(name => name.toLowerCase());
+  return [...new Set(names)];
+}
+
+// A minimal slice of the Public Suffix List: common multi-label registry
+// suffixes. Any single label (com, uk, ...) is treated as a public suffix too.
+const PUBLIC_SUFFIXES = new Set([
+  'co.uk', 'org.uk', 'ac.uk', 'gov.uk', 'me.uk', 'ltd.uk', 'plc.uk',
+  'com.au', 'net.au', 'org.au', 'edu.au', 'gov.au',
+  'co.jp', 'ne.jp', 'or.jp', 'ac.jp', 'go.jp',
+  'co.nz', 'org.nz', 'co.za', 'co.in', 'co.kr', 'or.kr',
+  'com.br', 'com.cn', 'com.mx', 'com.tr', 'com.tw', 'com.hk', 'com.sg',
+  'github.io', 'herokuapp.com', 'appspot.com', 'blogspot.com', 'azurewebsites.net', 'cloudfront.net',
+]);
+
+/**
+ * A wildcard SAN whose base is a public suffix (e.g. *.com or *.co.uk)
+ */
+function _isOverlyBroadWildcard(dnsName) {
+  if (!dnsName.startsWith('*.')) {
+    return false;
+  }
+  const base = dnsName.slice(2).toLowerCase().replace(/\.$/, '');
+  return !base.includes('.') || PUBLIC_SUFFIXES.has(base);
+}
+
+/**
+ * Decode one punycode label (RFC 3492), without the xn-- prefix
+ */
+function _decodePunycode(input) {
+  const base = 36;
+  const delimiter = input.lastIndexOf('-');
+  const output = delimiter > 0 ? Array.from(input.slice(0, delimiter)) : [];
+  let n = 128;
+  let bias = 72;
+  let i = 0;
+
+  for (let pos = delimiter > 0 ? delimiter + 1 : 0; pos < input.length;) {
+    const oldi = i;
+    for (let w = 1, k = base; ; k += base) {
+      if (pos >= input.length) {
+        throw new Error('Truncated punycode');
+      }
+      const c = input[pos++];
+      const digit = /[a-z]/.test(c) ? c.charCodeAt(0) - 97 : /[0-9]/.test(c) ? c.charCodeAt(0) - 22 : -1;
+      if (digit < 0) {
+        throw new Error('Invalid punycode digit');
+      }
+      i += digit * w;
+      const t = k <= bias ? 1 : k >= bias + 26 ? 26 : k - bias;
+      if (digit < t) {
+        break;
+      }
+      w *= base - t;
+    }
+
+    const length = output.length + 1;
+    let delta = oldi === 0 ? Math.floor((i - oldi) / 700) : (i - oldi) >> 1;
+    delta += Math.floor(delta / length);
+    let k = 0;
+    for (; delta > 455; k += base) {
+      delta = Math.floor(delta / 35);
+    }
+    bias = Math.floor(k + (36 * delta) / (delta + 38));
+
+    n += Math.floor(i / length);
+    i %= length;
+    output.splice(i++, 0, String.fromCodePoint(n));
+  }
+  return output.join('');
+}
+
+/**
+ * Unicode form of a DNS name: xn-- labels are decoded, and labels that fail to
+ * decode are kept as-is
+ */
+function _dnsNameToUnicode(name) {
+  return name.split('.').map(label => {
+    if (!/^xn--/i.test(label)) {
+      return label;
+    }
+    try {
+      return _decodePunycode(label.slice(4).toLowerCase());
+    } catch {
+      return label;
+    }
+  }).join('.');
+}
+
+function _looksLikeHostname(value) {
+  return typeof value === 'string' && /^(\*\.)?[a-z0-9_-]+(\.[a-z0-9_-]+)*\.?$/i.test(value);
+}
+
+/**
+ * A server leaf identified only by a hostname-like CN: modern clients ignore
+ * the CN and will reject it
+ */
+function _usesCnForHostname(cert, commonName, isCA, extendedKeyUsage) {
+  const isServerLeaf = !isCA && (extendedKeyUsage.length === 0 || extendedKeyUsage.includes('1.3.6.1.5.5.7.3.1'));
+  return isServerLeaf && _looksLikeHostname(commonName) && !_findExtension(cert, '2.5.29.17');
+}
+
+/**
+ * A CA whose NameConstraints permit only listed dNSName subtrees (and, when
+ * its EKU allows emailProtection, rfc822Name subtrees), in the Mozilla policy sense
+ */
+function _isTechnicallyConstrained(isCA, nameConstraints, extendedKeyUsage) {
+  if (!isCA || !nameConstraints) {
+    return false;
+  }
+  const permits = (type) => nameConstraints.permitted.some(name => name.type === type);
+  const allowsEmail = extendedKeyUsage.includes('1.3.6.1.5.5.7.3.4');
+  return permits('DNS') && (!allowsEmail || permits('email'));
+}
+
+const MS_PER_DAY = 24 * 60 * 60 * 1000;
+
+// Default allowance before a notBefore earlier than `now` counts as backdated
+export const BACKDATED_THRESHOLD_DAYS = 2;
+
+// SPKI SHA-256 pins of keys whose private halves are distributed with widely
+// installed software, so any certificate using one of them is compromised
+export const COMPROMISED_KEY_PINS = Object.freeze([
+  // CPython ssl test suite (Lib/test): localhost keys of current and older releases,
+  // test CA keys and the ECC key
+  'HSBUuRpVkWrQ3/fxOYivRKtgl7MQ+cdYfyY3NnvXwVA=',
+  'vkyJroQnil78EL1r+mFeP4S5qREQsXu1K6ajUeqhTU0=',
+  '7f4tJmqONKrhDn/fXbqvStN9D6ttPXOORj9ZwGazsMU=',
+  'eKnX70CAWhimi3NCFoYUU35hsXM8iHxPhs9NgC9tuyY=',
+  'UViUqIcZqNeccs6RUMXm59YYx1IDJW/I9RHbm50goMg=',
+  // Tornado's test server key (tornado/test/test.key)
+  '4tBhF9G21Fi9P8o4v+GB511Bimp3fs7F2u1WD2F0E5A=',
+  // xmlsec1 example RSA key (examples/rsakey.pem)
+  '717lKClBOuqlhMbzlaUNVU9fBdxS8VxJIr65IRLXBXY=',
+]);
+
+// Extract certificate information for display.
+// Pass { now } (typically the issuance or first-seen time) to get backdatedDays, the
+// whole days from notBefore to now, and backdated when that exceeds
+// { backdatedThresholdDays } (default BACKDATED_THRESHOLD_DAYS); both are null without now.
+// Pass { stable: true } to get extensions in a fixed (OID) order for diffs/snapshots,
+// and { oidNames: { [oid]: name } } to name private OIDs in RDNs, EKUs and policies.
+// Pass { checkCompromisedKeys: true } to flag keys on COMPROMISED_KEY_PINS as
+// knownCompromisedKey, or { compromisedKeyPins: [...] } (base64 SPKI SHA-256, like
+// spkiSha256Pin) to check against your own blocklist instead.
+export function extractCertificateInfo(cert, options = {}) {
+  const oidNames = options.oidNames || {};
+  const compromisedKeyPins = new Set(
+    options.compromisedKeyPins || (options.checkCompromisedKeys ? COMPROMISED_KEY_PINS : [])
+  );
+  const backdatedDays = options.now
+    ? Math.floor((options.now - cert.validity.notBefore) / MS_PER_DAY)
+    : null;
+  const subject = cert.subject.attributes.reduce((acc, attr) => {
+    acc[attr.shortName || attr.name] = _sanitizeNameValue(attr.value);
+    return acc;
+  }, {});
+
+  const issuer = cert.issuer.attributes.reduce((acc, attr) => {
+    acc[attr.shortName || attr.name] = _sanitizeNameValue(attr.value);
+    return acc;
+  }, {});
+
+  const altNames = _parseAltNames(cert, '2.5.29.17', 'subjectAltName');
+  const signatureAlgorithm = SIGNATURE_ALGORITHMS[cert.signatureOid];
+  const der = _certificateDer(cert);
+  const dnsNames = altNames.filter(name => name.type === 'DNS').map(name => name.value);
+  const extendedKeyUsage = _parseExtendedKeyUsage(cert);
+  const fingerprints = _fingerprints(der);
+  const isCA = cert.extensions.some(ext => 
+    ext.name === 'basicConstraints' && ext.cA === true
+  );
+  const nameConstraints = _parseNameConstraints(cert);
+  const keyUsage = _parseKeyUsage(cert);
+  const policies = _parseCertificatePolicies(cert, oidNames);
+  const certificatePolicies = policies.filter(policy => policy.oid !== ANY_POLICY_OID);
+
+  const subjectDN = _formatDistinguishedName(cert.subject.attributes, oidNames);
+  const issuerDN = _formatDistinguishedName(cert.issuer.attributes, oidNames);
+
+  return {
+    subject,
+    issuer,
+    subjectDN,
+    issuerDN,
+    // SHA-256 of the canonical DN (canonicalDnHash), for formatting-insensitive comparison
+    ..._dnHashes(cert, der),
+    // OpenSSL hashed-directory names (c_rehash): <subjectHash>.0
+    ..._opensslNameHashes(der),
+    subjectAttributes: _nameAttributes(cert.subject.attributes, oidNames),
+    issuerAttributes: _nameAttributes(cert.issuer.attributes, oidNames),
+    serialNumber: cert.serialNumber,
+    validFrom: cert.validity.notBefore,
+    validTo: cert.validity.notAfter,
+    backdatedDays,
+    backdated: backdatedDays === null ? null : backdatedDays > (options.backdatedThresholdDays ?? BACKDATED_THRESHOLD_DAYS),
+    subjectCommonName: subject.CN || 'Unknown',
+    // One label per certificate: CN, else first DNS SAN, else O
+    displayName: subject.CN || dnsNames[0] || subject.O || '(no name)',
+    issuerCommonName: issuer.CN || 'Unknown',
+    // An empty subject is allowed when the SAN identifies the certificate
+    emptySubject: cert.subject.attributes.length === 0,
+    emptyIssuer: cert.issuer.attributes.length === 0,
+    // RFC 5280 4.2.1.6: the SAN should only be critical when the subject is empty
+    sanCriticalWithSubject: cert.subject.attributes.length > 0 &&
+      cert.extensions.some(ext => ext.id === '2.5.29.17' && ext.critical),
+    // Control or bidi characters in a subject/issuer value (escaped as \uXXXX above)
+    suspiciousName: _hasSuspiciousNameValue(cert.subject.attributes) || _hasSuspiciousNameValue(cert.issuer.attributes),
+    isCA,
+    basicConstraintsError: _basicConstraintsError(cert),
+    isSelfSigned: JSON.stringify(subject) === JSON.stringify(issuer),
+    subjectAltNames: altNames,
+    issuerAltNames: _parseAltNames(cert, '2.5.29.18', 'issuerAltName'),
+    dnsNames,
+    // dnsNames with punycode (xn--) labels decoded, index for index
+    dnsNamesUnicode: dnsNames.map(_dnsNameToUnicode),
+    coveredNames: _coveredNames(subject.CN, dnsNames),
+    overlyBroadWildcard: dnsNames.some(_isOverlyBroadWildcard),
+    usesCnForHostname: _usesCnForHostname(cert, subject.CN, isCA, extendedKeyUsage),
+    ipAddresses: altNames.filter(name => name.type === 'IP').map(name => name.value),
+    keyUsage,
+    keyUsageCaContradiction: _keyUsageCaContradiction(cert, isCA, keyUsage),
+    netscapeCertType: _parseNamedBits(cert, '2.16.840.1.113730.1.1', NETSCAPE_CERT_TYPE_BITS, 'Netscape cert-type'),
+    extendedKeyUsage,
+    extendedKeyUsageNames: extendedKeyUsage.map(oid => _oidName(oid, oidNames) || oid),
+    ..._parseKeyIdentifiers(cert),
+    ..._parseAuthorityInfoAccess(cert),
+    subjectInfoAccess: _parseSubjectInfoAccess(cert),
+    smimeCapabilities: _parseSmimeCapabilities(cert),
+    crlDistributionPoints: _parseDistributionPointUrls(cert, '2.5.29.31'),
+    deltaCrlUrls: _parseDistributionPointUrls(cert, '2.5.29.46'),
+    ..._certificateKeyDetails(der),
+    signatureAlgorithm: signatureAlgorithm ? signatureAlgorithm.name : cert.signatureOid,
+    signatureHash: signatureAlgorithm ? signatureAlgorithm.hash : null,
+    nameConstraints,
+    isTechnicallyConstrained: _isTechnicallyConstrained(isCA, nameConstraints, extendedKeyUsage),
+    ..._parsePolicyExtensions(cert),
+    // anyPolicy is reported as hasAnyPolicy, not listed with the specific policies
+    certificatePolicies,
+    hasAnyPolicy: policies.length > certificatePolicies.length,
+    validationLevel: _validationLevel(certificatePolicies),
+    ..._parsePolicyQualifiers(cert),
+    logotypeUrls: _parseLogotypeUrls(cert),
+    extensions: _listExtensions(cert, options.stable === true),
+    derSizeBytes: der ? der.length : null,
+    pemSizeBytes: der ? _pemSize(der.length) : null,
+    ..._parseSignatureValue(der),
+    ...fingerprints,
+    knownCompromisedKey: fingerprints.spkiSha256Pin !== null && compromisedKeyPins.has(fingerprints.spkiSha256Pin),
+  };
+}
+
+/**
+ * Full value of a certificate PEM's extension as hex (the extnValue OCTET
+ * STRING contents), or null when the certificate does not have it
+ */
+export function extensionValueHex(pem, oid) {
+  const ext = certificateFromPem(pem).extensions.find(extension => extension.id === oid);
+  return ext ? forge.util.bytesToHex(ext.value) : null;
+}
+
+/**
+ * Locate the TBSCertificate fields of a DER certificate
+ */
+export function tbsCertificateFields(der) {
+  const tbs = fromDerRaw(der).value[0];
+  const offset = tbs.value[0].tagClass === forge.asn1.Class.CONTEXT_SPECIFIC ? 1 : 0;
+  return {
+    serialNumber: tbs.value[offset],
+    signature: tbs.value[offset + 1],
+    issuer: tbs.value[offset + 2],
+    validity: tbs.value[offset + 3],
+    subject: tbs.value[offset + 4],
+    subjectPublicKeyInfo: tbs.value[offset + 5],
+  };
+}
+
+/**
+ * Hex of an unsigned INTEGER's content bytes, without leading zero bytes, so
+ * encodings compare equal (serial numbers, CRL numbers, key components)
+ */
+export function unsignedHex(bytes) {
+  return forge.util.bytesToHex(bytes.replace(/^\x00+/, ''));
+}
+
+/**
+ * Extract a comparable { algorithm, key } from a SubjectPublicKeyInfo
+ */
+function _publicKeyFromSpki(spki) {
+  const oid = forge.asn1.derToOid(spki.value[0].value[0].value);
+  // Skip the BIT STRING's unused-bits byte
+  const keyBits = spki.value[1].value.substring(1);
+
+  switch (oid) {
+    case '1.2.840.113549.1.1.1': {
+      const rsaKey = fromDerRaw(keyBits);
+      return { algorithm: 'RSA', key: `${unsignedHex(rsaKey.value[0].value)}:${unsignedHex(rsaKey.value[1].value)}` };
+    }
+    case '1.2.840.10045.2.1':
+      return { algorithm: 'EC', key: forge.util.bytesToHex(keyBits) };
+    case '1.3.101.112':
+      return { algorithm: 'Ed25519', key: forge.util.bytesToHex(keyBits) };
+    default:
+      return { algorithm: oid, key: forge.util.bytesToHex(keyBits) };
+  }
+}
+
+// Named curves for EC keys: key size in bits, coordinate size in bytes, and
+// the digest and OID of the ECDSA signature algorithm used with each
+export const EC_CURVES = {
+  '1.2.840.10045.3.1.7': { namedCurve: 'P-256', bits: 256, size: 32, hash: 'SHA-256', signatureOid: '1.2.840.10045.4.3.2' },
+  '1.3.132.0.34': { namedCurve: 'P-384', bits: 384, size: 48, hash: 'SHA-384', signatureOid: '1.2.840.10045.4.3.3' },
+  '1.3.132.0.35': { namedCurve: 'P-521', bits: 521, size: 66, hash: 'SHA-512', signatureOid: '1.2.840.10045.4.3.4' },
+};
+
+// Smallest RSA modulus still accepted by browsers and CAs
+export const MIN_RSA_KEY_BITS = 2048;
+
+/**
+ * Key algorithm, size in bits and (for EC) curve of a SubjectPublicKeyInfo
+ * (an ASN.1 node): { algorithm, sizeBits, curve }. sizeBits is null for an
+ * unknown algorithm or curve, and an unknown curve is given by its OID.
+ */
+export function publicKeyDetails(spki) {
+  const oid = forge.asn1.derToOid(spki.value[0].value[0].value);
+  if (oid === '1.2.840.113549.1.1.1') {
+    const modulus = fromDerRaw(spki.value[1].value.substring(1)).value[0].value.replace(/^\x00+/, '');
+    return { algorithm: 'RSA', sizeBits: modulus.length * 8 - (Math.clz32(modulus.charCodeAt(0)) - 24), curve: null };
+  }
+  if (oid === '1.2.840.10045.2.1') {
+    const curveOid = forge.asn1.derToOid(spki.value[0].value[1].value);
+    const known = EC_CURVES[curveOid];
+    return { algorithm: 'EC', sizeBits: known ? known.bits : null, curve: known ? known.namedCurve : curveOid };
+  }
+  if (oid === '1.3.101.112') {
+    return { algorithm: 'Ed25519', sizeBits: 256, curve: null };
+  }
+  return { algorithm: oid, sizeBits: null, curve: null };
+}
+
+/**
+ * publicKeyDetails of a certificate's key as info fields; nulls where unknown
+ */
+function _certificateKeyDetails(der) {
+  const details = { publicKeyAlgorithm: null, publicKeySizeBits: null, publicKeyCurve: null };
+  if (!der) {
+    return details;
+  }
+  try {
+    const { algorithm, sizeBits, curve } = publicKeyDetails(tbsCertificateFields(der).subjectPublicKeyInfo);
+    details.publicKeyAlgorithm = algorithm;
+    details.publicKeySizeBits = sizeBits;
+    details.publicKeyCurve = curve;
+  } catch (e) {
+    console.debug('Failed to parse subjectPublicKeyInfo:', e);
+  }
+  return details;
+}
+
+function _publicKeyFromRsaPrivateKey(rsaKey) {
+  return { algorithm: 'RSA', key: `${unsignedHex(rsaKey.value[1].value)}:${unsignedHex(rsaKey.value[2].value)}` };
+}
+
+function _publicKeyFromEcPrivateKey(ecKey) {
+  // The public point is optional ([1] EXPLICIT BIT STRING) but written by OpenSSL
+  const publicKey = ecKey.value.find(node =>
+    node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === 1
+  );
+  return { algorithm: 'EC', key: publicKey ? forge.util.bytesToHex(publicKey.value[0].value.substring(1)) : null };
+}
+
+/**
+ * Extract a comparable { algorithm, key } from a PKCS#1, SEC1 or PKCS#8 private key PEM
+ */
+function _publicKeyFromPrivateKeyPem(pem) {
+  if (pem.includes('ENCRYPTED')) {
+    throw new Error('Private key is encrypted');
+  }
+  const der = forge.util.decode64(pem.replace(/-----(BEGIN|END) [A-Z ]+-----/g, '').replace(/\s/g, ''));
+  const asn1 = fromDerRaw(der);
+
+  if (pem.includes('RSA PRIVATE KEY')) {
+    return _publicKeyFromRsaPrivateKey(asn1);
+  }
+  if (pem.includes('EC PRIVATE KEY')) {
+    return _publicKeyFromEcPrivateKey(asn1);
+  }
+
+  // PKCS#8 PrivateKeyInfo: version, algorithm, privateKey [, attributes] [, publicKey]
+  const oid = forge.asn1.derToOid(asn1.value[1].value[0].value);
+  switch (oid) {
+    case '1.2.840.113549.1.1.1':
+      return _publicKeyFromRsaPrivateKey(fromDerRaw(asn1.value[2].value));
+    case '1.2.840.10045.2.1':
+      return _publicKeyFromEcPrivateKey(fromDerRaw(asn1.value[2].value));
+    default: {
+      const publicKey = asn1.value.find(node =>
+        node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === 1
+      );
+      const algorithm = oid === '1.3.101.112' ? 'Ed25519' : oid;
+      return { algorithm, key: publicKey ? forge.util.bytesToHex(publicKey.value.substring(1)) : null };
+    }
+  }
+}
+
+/**
+ * Check whether a private key belongs to a certificate by comparing public keys.
+ * Throws a CertificateError (ALGORITHM_MISMATCH) if the key types differ.
+ */
+export function keyMatchesCert(certPem, keyPem) {
+  const certKey = _publicKeyFromSpki(tbsCertificateFields(certificateFromPem(certPem).der).subjectPublicKeyInfo);
+  const privateKey = _publicKeyFromPrivateKeyPem(keyPem);
+
+  if (privateKey.algorithm !== certKey.algorithm) {
+    throw new CertificateError(
+      CertificateErrorCode.ALGORITHM_MISMATCH,
+      `Algorithm mismatch (${privateKey.algorithm} key vs ${certKey.algorithm} cert)`
+    );
+  }
+
+  return privateKey.key !== null && privateKey.key === certKey.key;
+}
+
+/**
+ * Pair each certificate with the index of its private key (or null)
+ */
+export function pairKeysToCerts(certPems, keyPems) {
+  const matches = (certPem, keyPem) => {
+    try {
+      return keyMatchesCert(certPem, keyPem);
+    } catch (e) {
+      console.debug('Skipping private key while pairing:', e);
+      return false;
+    }
+  };
+
+  return certPems.map((certPem, certIndex) => {
+    const keyIndex = keyPems.findIndex(keyPem => matches(certPem, keyPem));
+    return [certIndex, keyIndex === -1 ? null : keyIndex];
+  });
+}
+
+// Main function to parse any certificate file.
+// Files over options.maxInputBytes are rejected with an INPUT_TOO_LARGE CertificateError before reading,
+// and empty or whitespace-only files with an EMPTY_INPUT CertificateError.
+export async function parseCertificateFile(file, password = null, options = {}) {
+  const fileName = file.name.toLowerCase();
+  const fileExtension = fileName.split('.').pop();
+  const maxInputBytes = options.maxInputBytes ?? DEFAULT_MAX_INPUT_BYTES;
+  checkInputSize(file, maxInputBytes);
+  
+  return new Promise((resolve, reject) => {
+    const reader = new FileReader();
+    
+    reader.onload = async (e) => {
+      try {
+        checkNotEmpty(e.target.result, file.name);
+
+        let result = { certificates: [], privateKeys: [], needsPassword: false };
+        
+        // Try different formats based on extension and content
+        if (fileExtension === 'pfx' || fileExtension === 'p12') {
+          try {
+            result = await parsePKCS12(e.target.result, password || '');
+          } catch (error) {
+            if (error.message === 'INVALID_PASSWORD') {
+              result.needsPassword = true;
+            } else {
+              throw error;
+            }
+          }
+        } else if (fileExtension === 'json') {
+          result = parseJsonCertArray(forge.util.decodeUtf8(forge.util.createBuffer(e.target.result).getBytes()), { maxInputBytes });
+        } else if (fileExtension === 'der') {
+          // DER is always binary
+          result = parseDER(e.target.result);
+        } else if (fileExtension === 'crt' || fileExtension === 'cer') {
+          // CRT/CER can be DER, PEM or bare base64
+          result = parseCrtOrCer(e.target.result);
+        } else {
+          // Default to PEM, falling back to DER when there is no PEM block
+          result = parsePemOrDer(e.target.result, password);
+        }
+        
+        resolve(result);
+      } catch (error) {
+        reject(error);
+      }
+    };
+    
+    reader.onerror = () => reject(new Error('Failed to read file'));
+    
+    // Always read bytes: even PEM files may turn out to hold binary DER when
+    // the extension is misleading, and text is decoded after sniffing
+    reader.readAsArrayBuffer(file);
+  });
+}
+
+/**
+ * Whether `candidate` can be the issuer of `current` ({ cert, info } entries).
+ * A subject-less certificate can only be matched by its key identifier.
+ */
+function _chainIssuerMatches(current, candidate) {
+  return candidate.info.emptySubject
+    ? current.info.authorityKeyIdentifier !== null &&
+      current.info.authorityKeyIdentifier === candidate.info.subjectKeyIdentifier
+    : candidate.info.subjectDnHash === current.info.issuerDnHash;
+}
+
+/**
+ * Leaf certificates (non-CA or end-entity certs) and self-signed roots start a chain
+ */
+function _startsChain(entry) {
+  return !entry.info.isCA || entry.info.isSelfSigned;
+}
+
+/**
+ * Follow issuers from `leaf` through `entries` until a root or a missing link
+ */
+function _chainFrom(leaf, entries) {
+  const chainForLeaf = [];
+  let current = leaf;
+  const visited = new Set();
+
+  while (current && !visited.has(current)) {
+    visited.add(current);
+    chainForLeaf.push(current);
+
+    // Find issuer
+    if (current.info.isSelfSigned) {
+      break; // Reached root
+    }
+
+    const issuer = entries.find(entry => !visited.has(entry) && _chainIssuerMatches(current, entry));
+    if (!issuer) {
+      break; // Can't find issuer
+    }
+    current = issuer;
+  }
+
+  return chainForLeaf;
+}
+
+// Build certificate chain
+export function buildCertificateChain(certificates) {
+  const entries = certificates.map(certWrapper => {
+    const cert = certWrapper.data;
+    return { cert, info: extractCertificateInfo(cert), wrapper: certWrapper };
+  });
+
+  // For each leaf, try to build a chain
+  return entries.filter(_startsChain).map(leaf => _chainFrom(leaf, entries));
+}
+
+/**
+ * Incremental buildCertificateChain for UIs where certificates are added one
+ * at a time: each certificate is parsed once, when added, and only the chains
+ * it starts or extends are rebuilt. currentChains() returns the { cert, info,
+ * wrapper } chains buildCertificateChain would give for the distinct
+ * certificates added so far; unlike buildCertificateChain, a certificate
+ * added twice is kept only once.
+ */
+export class ChainBuilder {
+  constructor() {
+    this.entries = [];
+    this.chains = [];
+  }
+
+  /**
+   * Add a certificate PEM. Returns false (and changes nothing) when the same
+   * certificate was already added.
+   */
+  addCert(pem) {
+    const cert = certificateFromPem(pem);
+    if (this.entries.some(entry => entry.cert.der === cert.der)) {
+      return false;
+    }
+    const added = {
+      cert,
+      info: extractCertificateInfo(cert),
+      wrapper: { type: 'certificate', data: cert, pem: _encodePem(cert.der, 'CERTIFICATE') },
+    };
+    this.entries.push(added);
+
+    // Earlier links are unchanged: the new entry can only be found as the
+    // issuer of a chain's last certificate, which had none before
+    this.chains = this.chains.map(chain => {
+      const last = chain[chain.length - 1];
+      return !last.info.isSelfSigned && _chainIssuerMatches(last, added) ? _chainFrom(chain[0], this.entries) : chain;
+    });
+    if (_startsChain(added)) {
+      this.chains.push(_chainFrom(added, this.entries));
+    }
+    return true;
+  }
+
+  currentChains() {
+    return this.chains;
+  }
+}
+
+/**
+ * Describe the intended chain even when links were not uploaded. Each chain
+ * from buildCertificateChain that stops short of a self-signed root gets a
+ * trailing { present: false, fetchRequired: true } link naming the missing
+ * issuer's DN and where to download it (AIA caIssuers).
+ */
+export function planCertificateChain(certificates) {
+  return buildCertificateChain(certificates).map(chain => {
+    const links = chain.map(certInfo => ({
+      present: true,
+      fetchRequired: false,
+      subjectDN: certInfo.info.subjectDN,
+      caIssuerUrls: [],
+      certInfo,
+    }));
+
+    const last = chain[chain.length - 1];
+    if (!last.info.isSelfSigned) {
+      links.push({
+        present: false,
+        fetchRequired: true,
+        subjectDN: last.info.issuerDN,
+        caIssuerUrls: last.info.caIssuerUrls,
+        certInfo: null,
+      });
+    }
+    return links;
+  });
+}
+
+/**
+ * Recursively sort object keys and turn Dates into ISO strings
+ */
+function _stableValue(value) {
+  if (value instanceof Date) {
+    return value.toISOString();
+  }
+  if (Array.isArray(value)) {
+    return value.map(_stableValue);
+  }
+  if (value && typeof value === 'object') {
+    return Object.keys(value).sort().reduce((acc, key) => {
+      acc[key] = _stableValue(value[key]);
+      return acc;
+    }, {});
+  }
+  return value;
+}
+
+/**
+ * Serialize a parse result deterministically for golden tests: certificates,
+ * keys and other blocks stay in input order, object keys and extensions are sorted.
+ */
+export function toJsonStable(result) {
+  const stable = {
+    certificates: (result.certificates || []).map(certWrapper => ({
+      pem: certWrapper.pem,
+      info: extractCertificateInfo(certWrapper.data, { stable: true }),
+    })),
+    privateKeys: (result.privateKeys || []).map(key => ({ pem: key.pem, encrypted: key.encrypted })),
+    others: (result.others || []).map(item => ({ pemType: item.pemType, pem: item.pem })),
+  };
+  if (result.attributeCertificates) {
+    stable.attributeCertificates = result.attributeCertificates;
+  }
+  return JSON.stringify(_stableValue(stable), null, 2) + '\n';
+}
+
+/**
+ * Serialize a parsed certificate (an entry of `certificates` from any parser)
+ * with its PEM, base64 DER and every info field, plus PKCS#12 bag attributes
+ * when present. certificateFromJson turns it back into a wrapper that
+ * buildCertificateChain and the generators accept.
+ */
+export function certificateToJson(certWrapper) {
+  const der = certWrapper.data.der || forge.asn1.toDer(forge.pki.certificateToAsn1(certWrapper.data)).getBytes();
+  const json = {
+    type: certWrapper.type,
+    pem: certWrapper.pem,
+    der: forge.util.encode64(der),
+    info: extractCertificateInfo(certWrapper.data),
+  };
+  if (certWrapper.bagAttributes) {
+    json.bagAttributes = certWrapper.bagAttributes;
+  }
+  return JSON.stringify(json);
+}
+
+/**
+ * Rebuild a certificate wrapper from certificateToJson output (a string or the
+ * parsed object). The certificate comes from the stored DER; `info` is kept
+ * with its dates revived, so callers need not run extractCertificateInfo again.
+ */
+export function certificateFromJson(json, options = {}) {
+  checkInputSize(json, options.maxInputBytes);
+  checkNotEmpty(json);
+  const stored = typeof json === 'string' ? JSON.parse(json) : json;
+  if (!stored || typeof stored.der !== 'string') {
+    throw new Error('Not a serialized certificate: missing der');
+  }
+
+  const der = forge.util.decode64(stored.der);
+  const cert = safeCertificateFromAsn1(forge.asn1.fromDer(der));
+  cert.der = der;
+
+  const certWrapper = {
+    type: stored.type || 'certificate',
+    data: cert,
+    pem: stored.pem || _encodePem(der, 'CERTIFICATE'),
+  };
+  if (stored.info) {
+    certWrapper.info = { ...stored.info, validFrom: new Date(stored.info.validFrom), validTo: new Date(stored.info.validTo) };
+  }
+  if (stored.bagAttributes) {
+    certWrapper.bagAttributes = stored.bagAttributes;
+  }
+  return certWrapper;
+}
+
+// Generate nginx-ready certificate format.
+// Pass { reverse: true } for appliances that want the root first.
+export function generateNginxFormat(chain, privateKey, options = {}) {
+  let output = '';
+  const ordered = options.reverse ? [...chain].reverse() : chain;
+  
+  // Add certificates in order (leaf to root, or root to leaf when reversed)
+  ordered.forEach(certInfo => {
+    output += certInfo.wrapper.pem + '\n';
   });
   
   // Add private key if available
@@ -536,3 +2798,69 @@ export function generateNginxFormat(chain, privateKey) {
   
   return output.trim();
 }
+
+/**
+ * Wrap PEM strings the way parseCertificateFile returns certificates
+ */
+function _wrapCertificatePems(pems) {
+  return pems.map(pem => {
+    const cert = certificateFromPem(pem);
+    return { type: 'certificate', data: cert, pem: _encodePem(cert.der, 'CERTIFICATE') };
+  });
+}
+
+/**
+ * Generate nginx format from certificates in any order.
+ * The certificates must form exactly one leaf to root chain.
+ */
+export function generateNginxFormatFromPems(certPems, keyPem = null, options = {}) {
+  const certificates = _wrapCertificatePems(certPems);
+  const chain = buildCertificateChain(certificates).find(candidate => candidate.length === certificates.length);
+
+  if (!chain) {
+    throw new Error('Certificates do not form a single chain');
+  }
+
+  return generateNginxFormat(chain, keyPem ? { pem: keyPem.trim() } : null, options);
+}
+
+/**
+ * Generate nginx format from PEM strings the caller has already ordered
+ * (leaf first). Unlike generateNginxFormatFromPems, no chain is built or checked.
+ */
+export function generateNginxFormatFromOrderedPems(orderedPems, keyPem = null, options = {}) {
+  const chain = _wrapCertificatePems(orderedPems).map(wrapper => ({ wrapper }));
+  return generateNginxFormat(chain, keyPem ? { pem: keyPem.trim() } : null, options);
+}
+
+/**
+ * Split a chain into numbered PEM files (0.pem is the leaf, the root comes last).
+ * With { reverse: true } the numbering starts at the root instead.
+ */
+export function splitChain(chainPems, options = {}) {
+  const certificates = _wrapCertificatePems(chainPems);
+
+  // Use the longest chain the certificates form, in leaf to root order
+  const chain = buildCertificateChain(certificates)
+    .reduce((longest, candidate) => (candidate.length > longest.length ? candidate : longest), []);
+  const ordered = options.reverse ? [...chain].reverse() : chain;
+
+  return ordered.map((certInfo, index) => ({
+    fileName: `${index}.pem`,
+    contents: certInfo.wrapper.pem + '\n',
+  }));
+}
+
+/**
+ * Return only the intermediate CA certificates of a bundle (e.g. for Apache's
+ * SSLCertificateChainFile), ordered from the one that signs the leaf upward.
+ * The leaf and self-signed roots are left out.
+ */
+export function extractIntermediates(pems) {
+  const certificates = _wrapCertificatePems(pems);
+  const chain = buildCertificateChain(certificates)
+    .reduce((longest, candidate) => (candidate.length > longest.length ? candidate : longest), []);
+
+  const intermediates = chain.filter(certInfo => certInfo.info.isCA && !certInfo.info.isSelfSigned);
+  return intermediates.map(certInfo => certInfo.wrapper.pem + '\n').join('');
+}
@@ -0,0 +1,738 @@
+import { MIN_RSA_KEY_BITS, certificateFromPem, extractCertificateInfo, pkcs12ToPem, tbsCertificateFields } from './certificateParser.js';
+
+/**
+ * Certificate validation helpers: hostname matching, chain validation, trust
+ * diagnostics and RFC 5280 conformance
+ */
+
+// Digests browsers no longer accept in certificate signatures
+const WEAK_SIGNATURE_HASHES = ['md2', 'md5', 'sha1'];
+
+/**
+ * Reasons a browser would reject a certificate, in priority order
+ */
+export const TrustIssue = Object.freeze({
+  EXPIRED: 'expired',
+  NOT_YET_VALID: 'not_yet_valid',
+  HOSTNAME_MISMATCH: 'hostname_mismatch',
+  INCOMPLETE_CHAIN: 'incomplete_chain',
+  WEAK_SIGNATURE: 'weak_signature',
+  UNTRUSTED_ROOT: 'untrusted_root',
+  NAME_CONSTRAINT_VIOLATION: 'name_constraint_violation',
+});
+
+/**
+ * RFC 5280 profile violations reported by checkRfc5280
+ */
+export const ConformanceIssue = Object.freeze({
+  MALFORMED_BASIC_CONSTRAINTS: 'malformed_basic_constraints',
+  CA_MISSING_SKI: 'ca_missing_ski',
+  CA_MISSING_BASIC_CONSTRAINTS: 'ca_missing_basic_constraints',
+  KEY_CERT_SIGN_WITHOUT_CA: 'key_cert_sign_without_ca',
+  SERVER_LEAF_MISSING_SAN: 'server_leaf_missing_san',
+  EMPTY_SAN: 'empty_san',
+  SERIAL_NOT_POSITIVE: 'serial_not_positive',
+});
+
+/**
+ * Why pruneBundle dropped a certificate
+ */
+export const PruneReason = Object.freeze({
+  EXPIRED: 'expired',
+  UNUSED_INTERMEDIATE: 'unused_intermediate',
+});
+
+const SERVER_AUTH_EKU = '1.3.6.1.5.5.7.3.1';
+const MS_PER_DAY = 24 * 60 * 60 * 1000;
+
+// validateChain warns when the leaf expires within this many days
+const EXPIRY_WARNING_DAYS = 30;
+
+// CA/Browser Forum limits enforced by current browsers
+const MAX_LEAF_VALIDITY_DAYS = 398;
+const MIN_EC_KEY_BITS = 256;
+
+function _loadCertificate(pem) {
+  const cert = certificateFromPem(pem);
+  return { pem, cert, info: extractCertificateInfo(cert) };
+}
+
+/**
+ * Expand an IPv6 literal to the uncompressed form used for iPAddress SANs
+ */
+function _expandIpv6(address) {
+  const [head, tail] = address.split('::');
+  const headGroups = head ? head.split(':') : [];
+  const tailGroups = tail ? tail.split(':') : [];
+  const fill = address.includes('::') ? 8 - headGroups.length - tailGroups.length : 0;
+  return [...headGroups, ...Array(fill).fill('0'), ...tailGroups]
+    .map(group => parseInt(group, 16).toString(16))
+    .join(':');
+}
+
+function _dnsNameMatches(pattern, hostname) {
+  if (pattern === hostname) {
+    return true;
+  }
+  // Wildcards only cover exactly one leftmost label
+  if (!pattern.startsWith('*.')) {
+    return false;
+  }
+  const suffix = pattern.slice(1);
+  if (!hostname.endsWith(suffix)) {
+    return false;
+  }
+  const label = hostname.slice(0, hostname.length - suffix.length);
+  return label.length > 0 && !label.includes('.');
+}
+
+// Names compare case-insensitively, ignoring a single trailing (root) dot (RFC 6125)
+function _normalizeDnsName(name) {
+  const lower = name.toLowerCase();
+  return lower.endsWith('.') ? lower.slice(0, -1) : lower;
+}
+
+/**
+ * Check whether a certificate covers a hostname or IP address.
+ * Only subjectAltName entries are considered, as modern browsers ignore the CN.
+ */
+export function certMatchesHostname(info, hostname) {
+  const host = _normalizeDnsName(hostname);
+
+  if (/^\d{1,3}(\.\d{1,3}){3}$/.test(host)) {
+    return info.ipAddresses.includes(host);
+  }
+  if (host.includes(':')) {
+    return info.ipAddresses.includes(_expandIpv6(host));
+  }
+
+  return info.dnsNames.some(name => _dnsNameMatches(_normalizeDnsName(name), host));
+}
+
+/**
+ * Check a certificate's key against a pin set, as HPKP and mobile pinning do:
+ * true when its base64 SHA-256 SPKI pin (spkiSha256Pin) is one of `pins`.
+ * Pins may carry the "sha256/" prefix used by OkHttp and similar libraries.
+ */
+export function matchesPinSet(certPem, pins) {
+  const { info } = _loadCertificate(certPem);
+  return pins.some(pin => pin.trim().replace(/^sha256\//, '') === info.spkiSha256Pin);
+}
+
+function _isSelfIssued(entry) {
+  return entry.info.subjectDnHash === entry.info.issuerDnHash;
+}
+
+function _issuedBy(child, issuer) {
+  if (child.info.issuerDnHash !== issuer.info.subjectDnHash) {
+    return false;
+  }
+  const aki = child.info.authorityKeyIdentifier;
+  const ski = issuer.info.subjectKeyIdentifier;
+  return !aki || !ski || aki === ski;
+}
+
+/**
+ * Validate an ordered (leaf first) chain: every certificate must be within its
+ * validity period and issued by the next one.
+ *
+ * Weak signature digests are reported separately as warnings, since a modern
+ * leaf under a SHA-1 intermediate is still rejected by some clients. A
+ * self-issued root's own signature is not relied on and is ignored.
+ * Certificates that expire after their issuer are listed in `outlivesIssuer`.
+ */
+export function validateChain(chainPems, now = new Date()) {
+  const chain = chainPems.map(_loadCertificate);
+  const errors = [];
+
+  chain.forEach((entry, index) => {
+    const name = entry.info.subjectCommonName;
+    if (now > entry.info.validTo) {
+      errors.push(`Certificate "${name}" expired on ${entry.info.validTo.toISOString()}`);
+    } else if (now < entry.info.validFrom) {
+      errors.push(`Certificate "${name}" is not valid until ${entry.info.validFrom.toISOString()}`);
+    }
+
+    const issuer = chain[index + 1];
+    if (issuer && !_issuedBy(entry, issuer)) {
+      errors.push(`Certificate "${name}" is not issued by "${issuer.info.subjectCommonName}"`);
+    }
+
+    // Every certificate above the leaf acts as an issuer and must be a CA
+    if (index > 0 && !entry.info.isCA) {
+      errors.push(`Certificate "${name}" is not a CA (basicConstraints CA=false) and cannot issue certificates`);
+    }
+  });
+
+  // A certificate should not outlive the certificate that issued it
+  const outlivesIssuer = chain
+    .map((entry, index) => ({ entry, issuer: chain[index + 1], index }))
+    .filter(({ entry, issuer }) => issuer && entry.info.validTo > issuer.info.validTo)
+    .map(({ entry, issuer, index }) => ({
+      index,
+      subject: entry.info.subjectCommonName,
+      validTo: entry.info.validTo,
+      issuer: issuer.info.subjectCommonName,
+      issuerValidTo: issuer.info.validTo,
+    }));
+
+  const weakSignatures = chain
+    .map((entry, index) => ({
+      index,
+      subject: entry.info.subjectCommonName,
+      signatureAlgorithm: entry.info.signatureAlgorithm,
+      hash: entry.info.signatureHash,
+    }))
+    .filter(({ index, hash }) => {
+      const isRoot = index === chain.length - 1 && _isSelfIssued(chain[index]);
+      return !isRoot && WEAK_SIGNATURE_HASHES.includes(hash);
+    })
+    .map(({ index, subject, signatureAlgorithm }) => ({ index, subject, signatureAlgorithm }));
+
+  return {
+    valid: errors.length === 0,
+    errors,
+    chainHasWeakSignature: weakSignatures.length > 0,
+    weakSignatures,
+    outlivesIssuer,
+    warnings: _chainWarnings(chain, weakSignatures, outlivesIssuer, now),
+  };
+}
+
+/**
+ * Decrypt a PKCS#12 file, order its certificates from the leaf (the one the
+ * key belongs to) towards the root and validate them as a chain. Certificates
+ * that are not on the leaf's path are appended in file order, so they show up
+ * as broken links. Resolves to the validateChain result plus the ordered
+ * `chain` PEMs and whether that path is `complete` (ends at a self-issued root).
+ */
+export async function pkcs12ValidatedChain(bytes, password, now = new Date()) {
+  const { leaf, chain, others } = await pkcs12ToPem(bytes, password);
+  const entries = [leaf, ...chain, ...others].map(_loadCertificate);
+  const [leafEntry, ...rest] = entries;
+  const { path, complete } = _buildTrustPath(leafEntry, rest.filter(entry => !_isSelfIssued(entry)), rest.filter(_isSelfIssued));
+  const ordered = [...path, ...rest.filter(entry => !path.includes(entry))].map(entry => entry.pem);
+
+  return { ...validateChain(ordered, now), chain: ordered, complete };
+}
+
+/**
+ * Human-readable warnings for a whole chain, aggregated from the per-certificate
+ * flags (errors such as expiry or broken links are reported separately)
+ */
+function _chainWarnings(chain, weakSignatures, outlivesIssuer, now) {
+  const role = (index) => {
+    if (index === 0) {
+      return 'Leaf';
+    }
+    return index === chain.length - 1 && _isSelfIssued(chain[index]) ? 'Root' : 'Intermediate';
+  };
+  const warnings = [];
+
+  const leaf = chain[0];
+  if (leaf && now <= leaf.info.validTo) {
+    const days = _daysUntilExpiry(leaf.cert, now);
+    if (days < EXPIRY_WARNING_DAYS) {
+      warnings.push(`Leaf "${leaf.info.subjectCommonName}" expires in ${days} day${days === 1 ? '' : 's'}`);
+    }
+  }
+  if (leaf && leaf.info.usesCnForHostname) {
+    warnings.push(`Leaf "${leaf.info.subjectCommonName}" has no subjectAltName; browsers ignore the CN`);
+  }
+  if (leaf && leaf.info.overlyBroadWildcard) {
+    warnings.push(`Leaf "${leaf.info.subjectCommonName}" has a wildcard covering a public suffix`);
+  }
+
+  weakSignatures.forEach(({ index, subject, signatureAlgorithm }) => {
+    warnings.push(`${role(index)} "${subject}" uses a weak signature (${signatureAlgorithm})`);
+  });
+  outlivesIssuer.forEach(({ index, subject, issuer }) => {
+    warnings.push(`${role(index)} "${subject}" expires after its issuer "${issuer}"`);
+  });
+
+  if (chain.length > 1 && role(chain.length - 1) === 'Root') {
+    const root = chain[chain.length - 1];
+    warnings.push(`Root "${root.info.subjectCommonName}" is included unnecessarily; clients already have it`);
+  }
+  return warnings;
+}
+
+/**
+ * Walk from the leaf towards a root, preferring trusted roots over intermediates.
+ * Links are matched by issuer/subject DN and key identifiers.
+ */
+function _buildTrustPath(leaf, intermediates, roots) {
+  const path = [leaf];
+  let current = leaf;
+
+  for (;;) {
+    if (roots.some(root => root.cert.der === current.cert.der)) {
+      return { path, complete: true, anchored: true };
+    }
+    if (_isSelfIssued(current)) {
+      return { path, complete: true, anchored: false };
+    }
+
+    const issuer = roots.find(root => _issuedBy(current, root)) ||
+      intermediates.find(candidate => !path.includes(candidate) && _issuedBy(current, candidate));
+    if (!issuer) {
+      return { path, complete: false, anchored: false };
+    }
+
+    path.push(issuer);
+    current = issuer;
+  }
+}
+
+// The certificate that issued none of the others (the first one if unclear)
+function _findLeaf(entries) {
+  return entries.find(entry => !entries.some(other => other !== entry && _issuedBy(other, entry))) || entries[0];
+}
+
+/**
+ * Complete a leaf (plus any intermediates) into an ordered leaf-to-root chain,
+ * pulling missing intermediates and the root from a CA bundle. The leaf is
+ * the input certificate that issued none of the others.
+ */
+export function completeChain(leafAndIntermediatePems, caBundlePems) {
+  const inputs = leafAndIntermediatePems.map(_loadCertificate);
+  const bundle = caBundlePems.map(_loadCertificate);
+  if (inputs.length === 0) {
+    throw new Error('No leaf certificate provided');
+  }
+
+  const leaf = _findLeaf(inputs);
+  const candidates = [...inputs, ...bundle].filter(entry => entry !== leaf);
+  const roots = candidates.filter(_isSelfIssued);
+  const intermediates = candidates.filter(entry => !_isSelfIssued(entry));
+
+  const { path, complete } = _buildTrustPath(leaf, intermediates, roots);
+  if (!complete) {
+    const last = path[path.length - 1];
+    throw new Error(`Could not complete the chain: issuer "${last.info.issuerCommonName}" not found`);
+  }
+  return path.map(entry => entry.pem.trim());
+}
+
+function _withinDnsSubtree(name, base) {
+  const host = name.toLowerCase().replace(/^\*\./, '');
+  const constraint = base.toLowerCase();
+  if (constraint.startsWith('.')) {
+    return host.endsWith(constraint);
+  }
+  return host === constraint || host.endsWith('.' + constraint);
+}
+
+/**
+ * Return the leaf DNS names that a CA's nameConstraints do not allow
+ */
+function _nameConstraintViolations(leafInfo, constraints) {
+  const permitted = constraints.permitted.filter(name => name.type === 'DNS').map(name => name.value);
+  const excluded = constraints.excluded.filter(name => name.type === 'DNS').map(name => name.value);
+
+  return leafInfo.dnsNames.filter(name =>
+    (permitted.length > 0 && !permitted.some(base => _withinDnsSubtree(name, base))) ||
+    excluded.some(base => _withinDnsSubtree(name, base))
+  );
+}
+
+/**
+ * Explain why a browser would reject a certificate.
+ *
+ * Builds a path from the leaf through the intermediates to one of the trusted
+ * roots and returns a report with the reasons it fails, most important first.
+ * The chain is matched structurally (names and key identifiers); signatures are
+ * not verified.
+ */
+export function explainTrust(leafPem, intermediatePems = [], rootPems = [], hostname = null, now = new Date()) {
+  const leaf = _loadCertificate(leafPem);
+  const intermediates = intermediatePems.map(_loadCertificate);
+  const roots = rootPems.map(_loadCertificate);
+  const { path, complete, anchored } = _buildTrustPath(leaf, intermediates, roots);
+  const reasons = [];
+  const addReason = (code, entry, message) => {
+    reasons.push({ code, subject: entry.info.subjectCommonName, message });
+  };
+
+  path.forEach(entry => {
+    const name = entry.info.subjectCommonName;
+    if (now > entry.info.validTo) {
+      addReason(TrustIssue.EXPIRED, entry,
+        `Certificate "${name}" expired on ${entry.info.validTo.toISOString()}`);
+    } else if (now < entry.info.validFrom) {
+      addReason(TrustIssue.NOT_YET_VALID, entry,
+        `Certificate "${name}" is not valid until ${entry.info.validFrom.toISOString()}`);
+    }
+  });
+
+  if (hostname && !certMatchesHostname(leaf.info, hostname)) {
+    const covered = [...leaf.info.dnsNames, ...leaf.info.ipAddresses].join(', ') || 'no names';
+    addReason(TrustIssue.HOSTNAME_MISMATCH, leaf,
+      `Certificate is not valid for ${hostname} (it covers ${covered})`);
+  }
+
+  const last = path[path.length - 1];
+  if (!complete) {
+    addReason(TrustIssue.INCOMPLETE_CHAIN, last,
+      `Issuer certificate "${last.info.issuerDN}" for "${last.info.subjectCommonName}" is missing`);
+  }
+
+  path.forEach(entry => {
+    // A root's self-signature is never checked, so its digest does not matter
+    const isTrustAnchor = entry === last && complete;
+    if (!isTrustAnchor && WEAK_SIGNATURE_HASHES.includes(entry.info.signatureHash)) {
+      addReason(TrustIssue.WEAK_SIGNATURE, entry,
+        `Certificate "${entry.info.subjectCommonName}" is signed with ${entry.info.signatureAlgorithm}`);
+    }
+  });
+
+  if (complete && !anchored) {
+    addReason(TrustIssue.UNTRUSTED_ROOT, last,
+      `Root certificate "${last.info.subjectCommonName}" is not in the trusted root set`);
+  }
+
+  path.slice(1).forEach(ca => {
+    if (!ca.info.nameConstraints) {
+      return;
+    }
+    const violations = _nameConstraintViolations(leaf.info, ca.info.nameConstraints);
+    if (violations.length > 0) {
+      addReason(TrustIssue.NAME_CONSTRAINT_VIOLATION, ca,
+        `"${ca.info.subjectCommonName}" is not allowed to issue for ${violations.join(', ')}`);
+    }
+  });
+
+  return {
+    trusted: reasons.length === 0,
+    chain: path.map(entry => entry.info),
+    reasons,
+  };
+}
+
+/**
+ * Check a certificate against the RFC 5280 profile rules that CAs commonly get
+ * wrong. Returns a list of { code, message }; an empty list means no issues.
+ */
+export function checkRfc5280(pem) {
+  const { cert, info } = _loadCertificate(pem);
+  const issues = [];
+  const addIssue = (code, message) => issues.push({ code, message });
+
+  const hasExtension = (oid) => info.extensions.some(ext => ext.oid === oid);
+  const hasBasicConstraints = hasExtension('2.5.29.19');
+  const canSignCertificates = info.keyUsage.includes('keyCertSign');
+  const actsAsCA = info.isCA || canSignCertificates;
+
+  if (info.basicConstraintsError) {
+    addIssue(ConformanceIssue.MALFORMED_BASIC_CONSTRAINTS,
+      'basicConstraints is present but cannot be decoded, so the CA flag is unknown (4.2.1.9)');
+  }
+  if (actsAsCA && !info.subjectKeyIdentifier) {
+    addIssue(ConformanceIssue.CA_MISSING_SKI,
+      'CA certificates must include the subjectKeyIdentifier extension (4.2.1.2)');
+  }
+  if (canSignCertificates && !hasBasicConstraints) {
+    addIssue(ConformanceIssue.CA_MISSING_BASIC_CONSTRAINTS,
+      'CA certificates must include basicConstraints with cA set (4.2.1.9)');
+  } else if (canSignCertificates && !info.isCA && !info.basicConstraintsError) {
+    addIssue(ConformanceIssue.KEY_CERT_SIGN_WITHOUT_CA,
+      'keyCertSign is asserted but basicConstraints cA is not set (4.2.1.3)');
+  }
+
+  if (hasExtension('2.5.29.17')) {
+    if (info.subjectAltNames.length === 0) {
+      addIssue(ConformanceIssue.EMPTY_SAN,
+        'subjectAltName must contain at least one name (4.2.1.6)');
+    }
+  } else if (!actsAsCA && info.extendedKeyUsage.includes(SERVER_AUTH_EKU)) {
+    addIssue(ConformanceIssue.SERVER_LEAF_MISSING_SAN,
+      `Server certificate "${info.subjectCommonName}" has no subjectAltName; clients ignore the CN`);
+  }
+
+  const serial = tbsCertificateFields(cert.der).serialNumber.value;
+  if ((serial.charCodeAt(0) & 0x80) !== 0 || /^\x00*$/.test(serial)) {
+    addIssue(ConformanceIssue.SERIAL_NOT_POSITIVE,
+      'The serial number must be a positive integer (4.1.2.2)');
+  }
+
+  return issues;
+}
+
+function _daysUntilExpiry(cert, now) {
+  return Math.floor((cert.validity.notAfter.getTime() - now.getTime()) / MS_PER_DAY);
+}
+
+/**
+ * True when the certificate expires within `renewBeforeDays` days (or already has)
+ */
+export function inRenewalWindow(pem, renewBeforeDays, now = new Date()) {
+  return _daysUntilExpiry(certificateFromPem(pem), now) <= renewBeforeDays;
+}
+
+/**
+ * The earliest-expiring certificate of a set as { index, notAfter }, or null
+ * for an empty set
+ */
+export function soonestExpiry(pems) {
+  return pems.reduce((soonest, pem, index) => {
+    const notAfter = certificateFromPem(pem).validity.notAfter;
+    return !soonest || notAfter < soonest.notAfter ? { index, notAfter } : soonest;
+  }, null);
+}
+
+/**
+ * Serial numbers an issuer used for more than one distinct certificate, a
+ * serious CA failure: [{ issuer, serialNumber, indices }] with the input
+ * positions of every certificate in the group. The same certificate listed
+ * twice is not a reuse.
+ */
+export function duplicateSerials(pems) {
+  const groups = new Map();
+  pems.map(_loadCertificate).forEach((entry, index) => {
+    const key = `${entry.info.issuerDnHash}\n${entry.info.serialNumber}`;
+    if (!groups.has(key)) {
+      groups.set(key, { issuer: entry.info.issuerDN, serialNumber: entry.info.serialNumber, indices: [], der: new Set() });
+    }
+    const group = groups.get(key);
+    group.indices.push(index);
+    group.der.add(entry.cert.der);
+  });
+
+  return [...groups.values()]
+    .filter(group => group.der.size > 1)
+    .map(({ issuer, serialNumber, indices }) => ({ issuer, serialNumber, indices }));
+}
+
+// Orderings accepted by bundleReport
+const REPORT_SORTS = {
+  input: () => 0,
+  expiry: (a, b) => a.notAfter - b.notAfter,
+  subject: (a, b) => a.subject.localeCompare(b.subject),
+};
+
+/**
+ * Inventory rows for a set of certificates: { index, subject, issuer,
+ * notBefore, notAfter, daysUntilExpiry, sha256Fingerprint }, where index is
+ * the input position. options.sort is 'input' (default), 'expiry' (soonest
+ * first) or 'subject'; ties keep input order.
+ */
+export function bundleReport(pems, options = {}) {
+  const sort = options.sort ?? 'input';
+  const now = options.now ?? new Date();
+  if (!REPORT_SORTS[sort]) {
+    throw new Error(`Unknown report sort: ${sort}`);
+  }
+
+  const rows = pems.map(_loadCertificate).map((entry, index) => ({
+    index,
+    subject: entry.info.subjectDN,
+    issuer: entry.info.issuerDN,
+    notBefore: entry.info.validFrom,
+    notAfter: entry.info.validTo,
+    daysUntilExpiry: _daysUntilExpiry(entry.cert, now),
+    sha256Fingerprint: entry.info.sha256Fingerprint,
+  }));
+  return rows.sort((a, b) => REPORT_SORTS[sort](a, b) || a.index - b.index);
+}
+
+/**
+ * Compare a certificate with the one it may renew: same public key (same-key
+ * renewal vs re-key), same covered names, and whether the issuer changed.
+ * It counts as a renewal when either the key or the names carry over.
+ */
+export function isRenewalOf(newPem, oldPem) {
+  const renewed = _loadCertificate(newPem).info;
+  const previous = _loadCertificate(oldPem).info;
+  const sortedNames = (info) => [...info.coveredNames].sort().join('\n');
+
+  const sameKey = renewed.spkiSha256Pin === previous.spkiSha256Pin;
+  const sameNames = sortedNames(renewed) === sortedNames(previous);
+  return {
+    isRenewal: sameKey || sameNames,
+    sameKey,
+    sameNames,
+    issuerChanged: renewed.issuerDnHash !== previous.issuerDnHash,
+  };
+}
+
+/**
+ * Trim a bundle: drop expired certificates, then intermediates that lie on no
+ * leaf's chain within the set (a bundle without leaves keeps its intermediates).
+ * Returns { kept: [pem], removed: [{ pem, subject, reason }] } in bundle order.
+ */
+export function pruneBundle(pems, now = new Date()) {
+  const entries = pems.map(_loadCertificate);
+  const expired = new Set(entries.filter(entry => entry.cert.validity.notAfter < now));
+  const current = entries.filter(entry => !expired.has(entry));
+
+  const leaves = current.filter(entry => !entry.info.isCA);
+  const intermediates = current.filter(entry => entry.info.isCA && !_isSelfIssued(entry));
+  const roots = current.filter(_isSelfIssued);
+  const used = new Set();
+  for (const leaf of leaves) {
+    _buildTrustPath(leaf, intermediates, roots).path.forEach(entry => used.add(entry));
+  }
+
+  const kept = [];
+  const removed = [];
+  for (const entry of entries) {
+    let reason = null;
+    if (expired.has(entry)) {
+      reason = PruneReason.EXPIRED;
+    } else if (leaves.length > 0 && intermediates.includes(entry) && !used.has(entry)) {
+      reason = PruneReason.UNUSED_INTERMEDIATE;
+    }
+
+    if (reason) {
+      removed.push({ pem: entry.pem, subject: entry.info.subjectDN, reason });
+    } else {
+      kept.push(entry.pem);
+    }
+  }
+  return { kept, removed };
+}
+
+/**
+ * One-call deployment checklist for a served chain, for a traffic-light UI.
+ * `complete` means the chain reaches a root or at least an intermediate CA (the
+ * root is expected in the client's trust store); `noWeakCrypto` covers weak
+ * signature digests below the root. hostnameCovered is null without a hostname.
+ * The grade is F when any of the first four checks fails, C for weak crypto,
+ * B when only the root is included, and A otherwise.
+ */
+export function chainHealth(chainPems, hostname = null, now = new Date()) {
+  const entries = chainPems.map(_loadCertificate);
+  if (entries.length === 0) {
+    throw new Error('No certificates provided');
+  }
+
+  const leaf = _findLeaf(entries);
+  const others = entries.filter(entry => entry !== leaf);
+  const trust = _buildTrustPath(leaf, others.filter(entry => !_isSelfIssued(entry)), others.filter(_isSelfIssued));
+  const last = trust.path[trust.path.length - 1];
+
+  const checks = {
+    complete: trust.complete || (last !== leaf && last.info.isCA),
+    correctlyOrdered: entries[0] === leaf && entries.every((entry, index) =>
+      index === entries.length - 1 || _issuedBy(entry, entries[index + 1])
+    ),
+    allValidDates: entries.every(entry => now >= entry.info.validFrom && now <= entry.info.validTo),
+    hostnameCovered: hostname ? certMatchesHostname(leaf.info, hostname) : null,
+    noWeakCrypto: trust.path.every(entry => _isSelfIssued(entry) || !WEAK_SIGNATURE_HASHES.includes(entry.info.signatureHash)),
+    rootNotIncluded: !entries.some(_isSelfIssued),
+  };
+
+  let grade = 'A';
+  if (!checks.complete || !checks.correctlyOrdered || !checks.allValidDates || checks.hostnameCovered === false) {
+    grade = 'F';
+  } else if (!checks.noWeakCrypto) {
+    grade = 'C';
+  } else if (!checks.rootNotIncluded) {
+    grade = 'B';
+  }
+  return { ...checks, grade };
+}
+
+/**
+ * Size of a chain and whether it carries extra weight: { length, hasRoot,
+ * isMinimal }. A chain is minimal when every certificate is on the leaf's
+ * path and none is repeated, so removing any one would break it (the root
+ * may be included or not).
+ */
+export function chainStats(chainPems) {
+  const entries = chainPems.map(_loadCertificate);
+  if (entries.length === 0) {
+    throw new Error('No certificates provided');
+  }
+
+  const leaf = _findLeaf(entries);
+  const others = entries.filter(entry => entry !== leaf);
+  const { path } = _buildTrustPath(leaf, others.filter(entry => !_isSelfIssued(entry)), others.filter(_isSelfIssued));
+  const distinct = new Set(entries.map(entry => entry.cert.der));
+
+  return {
+    length: entries.length,
+    hasRoot: entries.some(entry => entry !== leaf && _isSelfIssued(entry)),
+    isMinimal: distinct.size === entries.length && path.length === entries.length,
+  };
+}
+
+/**
+ * Issuers referenced by a set of certificates but not present in it, for chain
+ * repair: one { issuerDN, authorityKeyIdentifier, caIssuerUrls, issuedTo }
+ * per distinct issuer (by DN and AKI), where issuedTo lists the subject DNs
+ * that need it. Self-issued certificates are skipped.
+ */
+export function missingIssuers(pems) {
+  const entries = pems.map(_loadCertificate);
+  const missing = new Map();
+
+  for (const entry of entries) {
+    if (_isSelfIssued(entry) || entries.some(candidate => _issuedBy(entry, candidate))) {
+      continue;
+    }
+    const { issuerDN, issuerDnHash, authorityKeyIdentifier, caIssuerUrls, subjectDN } = entry.info;
+    const key = `${issuerDnHash}\n${authorityKeyIdentifier || ''}`;
+    if (!missing.has(key)) {
+      missing.set(key, { issuerDN, authorityKeyIdentifier, caIssuerUrls: [], issuedTo: [] });
+    }
+    const issuer = missing.get(key);
+    issuer.caIssuerUrls.push(...caIssuerUrls.filter(url => !issuer.caIssuerUrls.includes(url)));
+    issuer.issuedTo.push(subjectDN);
+  }
+  return [...missing.values()];
+}
+
+/**
+ * Whether a current browser would accept an ordered (leaf first) chain:
+ * the leaf has a SAN, covers `hostname` (skipped when null), lasts at most
+ * 398 days and is within its validity period; no certificate below the root
+ * is signed with SHA-1 or weaker; every key is RSA >= 2048 or EC >= P-256;
+ * and the chain reaches a root, either included last or given in rootPems.
+ * Returns { accepted, reasons } with one human-readable reason per failure.
+ */
+export function meetsBrowserProfile(chainPems, hostname = null, now = new Date(), rootPems = []) {
+  const chain = chainPems.map(_loadCertificate);
+  if (chain.length === 0) {
+    throw new Error('No certificates provided');
+  }
+  const [leaf, ...rest] = chain;
+  const roots = [...rest.filter(_isSelfIssued), ...rootPems.map(_loadCertificate)];
+  const { path, complete } = _buildTrustPath(leaf, rest.filter(entry => !_isSelfIssued(entry)), roots);
+  const last = path[path.length - 1];
+  const reasons = [];
+
+  const leafName = leaf.info.subjectCommonName;
+  if (leaf.info.dnsNames.length === 0 && leaf.info.ipAddresses.length === 0) {
+    reasons.push(`Leaf "${leafName}" has no subjectAltName`);
+  }
+  if (hostname && !certMatchesHostname(leaf.info, hostname)) {
+    reasons.push(`Leaf "${leafName}" does not cover ${hostname}`);
+  }
+  const validityDays = (leaf.info.validTo - leaf.info.validFrom) / MS_PER_DAY;
+  if (validityDays > MAX_LEAF_VALIDITY_DAYS) {
+    reasons.push(`Leaf "${leafName}" is valid for ${Math.ceil(validityDays)} days (maximum ${MAX_LEAF_VALIDITY_DAYS})`);
+  }
+  if (now > leaf.info.validTo || now < leaf.info.validFrom) {
+    reasons.push(`Leaf "${leafName}" is outside its validity period`);
+  }
+
+  path.forEach(entry => {
+    const name = entry.info.subjectCommonName;
+    const isTrustAnchor = entry === last && complete && entry !== leaf;
+    if (!isTrustAnchor && WEAK_SIGNATURE_HASHES.includes(entry.info.signatureHash)) {
+      reasons.push(`Certificate "${name}" is signed with ${entry.info.signatureAlgorithm}`);
+    }
+    const { publicKeyAlgorithm, publicKeySizeBits } = entry.info;
+    const minBits = { RSA: MIN_RSA_KEY_BITS, EC: MIN_EC_KEY_BITS }[publicKeyAlgorithm];
+    if (!minBits || !publicKeySizeBits || publicKeySizeBits < minBits) {
+      const size = publicKeySizeBits ? ` ${publicKeySizeBits}-bit` : '';
+      reasons.push(`Certificate "${name}" has a weak or unsupported${size} ${publicKeyAlgorithm} key`);
+    }
+  });
+
+  if (!complete) {
+    reasons.push(`Chain does not reach a root: issuer "${last.info.issuerDN}" is missing`);
+  }
+  return { accepted: reasons.length === 0, reasons };
+}
@@ -0,0 +1,18 @@
+/**
+ * Typed errors for certificate and key handling
+ */
+
+export const CertificateErrorCode = Object.freeze({
+  ALGORITHM_MISMATCH: 'algorithm_mismatch',
+  INPUT_TOO_LARGE: 'input_too_large',
+  EMPTY_INPUT: 'empty_input',
+  TRUNCATED_INPUT: 'truncated_input',
+});
+
+export class CertificateError extends Error {
+  constructor(code, message) {
+    super(message);
+    this.name = 'CertificateError';
+    this.code = code;
+  }
+}
@@ -0,0 +1,419 @@
+import forge from 'node-forge';
+import {
+  SIGNATURE_ALGORITHMS,
+  canonicalDnHash,
+  certificateFromPem,
+  checkInputSize,
+  checkNotEmpty,
+  distinguishedNameFromAsn1,
+  fromDerRaw,
+  parseTime,
+  safeCertificateToPem,
+  tbsCertificateFields,
+  toBytes,
+  unsignedHex,
+} from './certificateParser.js';
+import { verifySignature, verifyCertificateSignature } from './signatures.js';
+
+/**
+ * Revocation checking: OCSP (RFC 6960) and CRLs (RFC 5280 section 5)
+ */
+
+const OCSP_RESPONSE_STATUSES = [
+  'successful',
+  'malformedRequest',
+  'internalError',
+  'tryLater',
+  null,
+  'sigRequired',
+  'unauthorized',
+];
+
+/**
+ * CRLReason codes (RFC 5280 section 5.3.1)
+ */
+export const REVOCATION_REASONS = {
+  0: 'unspecified',
+  1: 'keyCompromise',
+  2: 'cACompromise',
+  3: 'affiliationChanged',
+  4: 'superseded',
+  5: 'cessationOfOperation',
+  6: 'certificateHold',
+  8: 'removeFromCRL',
+  9: 'privilegeWithdrawn',
+  10: 'aACompromise',
+};
+
+const HASH_ALGORITHMS = {
+  '1.3.14.3.2.26': 'sha1',
+  '2.16.840.1.101.3.4.2.1': 'sha256',
+  '2.16.840.1.101.3.4.2.2': 'sha384',
+  '2.16.840.1.101.3.4.2.3': 'sha512',
+};
+
+const OCSP_BASIC_RESPONSE = '1.3.6.1.5.5.7.48.1.1';
+const OCSP_SIGNING_EKU = '1.3.6.1.5.5.7.3.9';
+
+function _isContext(node, type) {
+  return node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === type;
+}
+
+function _hash(algorithm, bytes) {
+  return forge.md[algorithm].create().update(bytes).digest().getBytes();
+}
+
+/**
+ * The subject DN and public key bits (without the unused-bits byte) of a certificate
+ */
+function _certificateIdentity(cert) {
+  const fields = tbsCertificateFields(cert.der);
+  return {
+    subjectDer: forge.asn1.toDer(fields.subject).getBytes(),
+    subjectName: fields.subject,
+    keyBits: fields.subjectPublicKeyInfo.value[1].value.substring(1),
+    spkiDer: forge.asn1.toDer(fields.subjectPublicKeyInfo).getBytes(),
+    serialNumber: unsignedHex(fields.serialNumber.value),
+  };
+}
+
+/**
+ * Compute the CertID fields (as hex) identifying a certificate to a responder
+ */
+function _certId(certIdentity, issuerIdentity, hashAlgorithm) {
+  return {
+    hashAlgorithm,
+    issuerNameHash: forge.util.bytesToHex(_hash(hashAlgorithm, issuerIdentity.subjectDer)),
+    issuerKeyHash: forge.util.bytesToHex(_hash(hashAlgorithm, issuerIdentity.keyBits)),
+    serialNumber: certIdentity.serialNumber,
+  };
+}
+
+/**
+ * Parse a SingleResponse with its CertID and status
+ */
+function _parseSingleResponse(node) {
+  const [certId, certStatus, thisUpdate, ...optional] = node.value;
+  const nextUpdate = optional.find(child => _isContext(child, 0));
+  const response = {
+    certId: {
+      hashAlgorithm: HASH_ALGORITHMS[forge.asn1.derToOid(certId.value[0].value[0].value)] || null,
+      issuerNameHash: forge.util.bytesToHex(certId.value[1].value),
+      issuerKeyHash: forge.util.bytesToHex(certId.value[2].value),
+      serialNumber: unsignedHex(certId.value[3].value),
+    },
+    status: ['good', 'revoked', 'unknown'][certStatus.type] || 'unknown',
+    revocationTime: null,
+    revocationReason: null,
+    thisUpdate: parseTime(thisUpdate),
+    nextUpdate: nextUpdate ? parseTime(nextUpdate.value[0]) : null,
+  };
+
+  if (response.status === 'revoked') {
+    // RevokedInfo: revocationTime, revocationReason [0] EXPLICIT CRLReason OPTIONAL
+    response.revocationTime = parseTime(certStatus.value[0]);
+    const reason = certStatus.value.find(child => _isContext(child, 0));
+    if (reason) {
+      const code = reason.value[0].value.charCodeAt(0);
+      response.revocationReason = REVOCATION_REASONS[code] || String(code);
+    }
+  }
+
+  return response;
+}
+
+/**
+ * Decode an OCSPResponse, keeping the signed bytes needed for verification
+ */
+function _decodeOcspResponse(der, maxInputBytes) {
+  checkInputSize(der, maxInputBytes);
+  checkNotEmpty(der);
+  const bytes = typeof der === 'string' ? der : forge.util.createBuffer(der).getBytes();
+  const asn1 = fromDerRaw(bytes);
+  const status = OCSP_RESPONSE_STATUSES[asn1.value[0].value.charCodeAt(0)] || 'unknown';
+  if (status !== 'successful') {
+    return { response: { status, responderId: null, producedAt: null, responses: [], certificates: [] }, signed: null };
+  }
+
+  const responseBytes = asn1.value[1].value[0];
+  if (forge.asn1.derToOid(responseBytes.value[0].value) !== OCSP_BASIC_RESPONSE) {
+    throw new Error('Unsupported OCSP response type');
+  }
+
+  const basic = fromDerRaw(responseBytes.value[1].value);
+  const [tbsResponseData, signatureAlgorithm, signature, certs] = basic.value;
+  // Skip the optional version [0]
+  const [responderId, producedAt, responses] = tbsResponseData.value.filter(child => !_isContext(child, 0));
+
+  return {
+    response: {
+      status,
+      // ResponderID: byName [1] Name | byKey [2] OCTET STRING (SHA-1 of the key)
+      responderId: responderId.type === 1
+        ? { byName: distinguishedNameFromAsn1(responderId.value[0]), byKey: null }
+        : { byName: null, byKey: forge.util.bytesToHex(responderId.value[0].value) },
+      producedAt: parseTime(producedAt),
+      responses: responses.value.map(_parseSingleResponse),
+      certificates: certs ? certs.value[0].value.map(safeCertificateToPem) : [],
+    },
+    signed: {
+      tbs: forge.asn1.toDer(tbsResponseData).getBytes(),
+      signatureOid: forge.asn1.derToOid(signatureAlgorithm.value[0].value),
+      // Skip the BIT STRING's unused-bits byte
+      signature: signature.value.substring(1),
+      responderName: responderId.type === 1 ? responderId.value[0] : null,
+    },
+  };
+}
+
+/**
+ * Parse a DER OCSPResponse (BasicOCSPResponse) into its status, responder,
+ * single responses and any included responder certificates
+ */
+export function parseOcspResponse(der, options = {}) {
+  return _decodeOcspResponse(der, options.maxInputBytes).response;
+}
+
+function _isResponder(responderId, responderName, identity) {
+  if (responderName) {
+    return canonicalDnHash(responderName).sha256 === canonicalDnHash(identity.subjectName).sha256;
+  }
+  return responderId.byKey === forge.util.bytesToHex(_hash('sha1', identity.keyBits));
+}
+
+function _hasOcspSigningUsage(cert) {
+  const ext = cert.extensions.find(extension => extension.id === '2.5.29.37');
+  if (!ext) {
+    return false;
+  }
+  return fromDerRaw(ext.value).value.some(node => forge.asn1.derToOid(node.value) === OCSP_SIGNING_EKU);
+}
+
+/**
+ * Find the key that signed the response: the issuer itself, or a delegated
+ * responder certificate issued by it with the OCSPSigning extended key usage
+ */
+async function _findResponderKey(response, responderName, issuer, issuerIdentity) {
+  if (_isResponder(response.responderId, responderName, issuerIdentity)) {
+    return issuerIdentity.spkiDer;
+  }
+
+  for (const pem of response.certificates) {
+    const candidate = certificateFromPem(pem);
+    const identity = _certificateIdentity(candidate);
+    if (!_isResponder(response.responderId, responderName, identity)) {
+      continue;
+    }
+    if (!_hasOcspSigningUsage(candidate)) {
+      throw new Error('OCSP responder certificate is not authorized for OCSP signing');
+    }
+    if (!await verifyCertificateSignature(candidate, issuer)) {
+      throw new Error('OCSP responder certificate is not issued by the certificate issuer');
+    }
+    return identity.spkiDer;
+  }
+
+  throw new Error('OCSP responder certificate not found');
+}
+
+/**
+ * Verify an OCSP response for a certificate and return its revocation status.
+ *
+ * The response must contain a SingleResponse whose CertID matches the
+ * certificate (issuer name/key hashes and serial), and be signed by the issuer
+ * or by a delegated responder the issuer authorized, and be current at `now`:
+ * a thisUpdate in the future or a nextUpdate in the past is rejected. Returns
+ * { status: 'good' | 'revoked' | 'unknown', revocationTime, revocationReason,
+ * thisUpdate, nextUpdate }.
+ */
+export async function verifyOcsp(responseDer, certPem, issuerPem, now = new Date()) {
+  const { response, signed } = _decodeOcspResponse(responseDer);
+  if (response.status !== 'successful') {
+    throw new Error(`OCSP responder returned ${response.status}`);
+  }
+
+  const cert = certificateFromPem(certPem);
+  const issuer = certificateFromPem(issuerPem);
+  const certIdentity = _certificateIdentity(cert);
+  const issuerIdentity = _certificateIdentity(issuer);
+
+  const single = response.responses.find(({ certId }) => {
+    if (!certId.hashAlgorithm) {
+      return false;
+    }
+    const expected = _certId(certIdentity, issuerIdentity, certId.hashAlgorithm);
+    return certId.serialNumber === expected.serialNumber &&
+      certId.issuerNameHash === expected.issuerNameHash &&
+      certId.issuerKeyHash === expected.issuerKeyHash;
+  });
+  if (!single) {
+    throw new Error('OCSP response does not cover this certificate');
+  }
+
+  const responderKey = await _findResponderKey(response, signed.responderName, issuer, issuerIdentity);
+  if (!await verifySignature(responderKey, signed.signatureOid, signed.tbs, signed.signature)) {
+    throw new Error('OCSP response signature is invalid');
+  }
+  if (single.thisUpdate > now) {
+    throw new Error(`OCSP response is not yet valid (thisUpdate ${single.thisUpdate.toISOString()})`);
+  }
+  if (single.nextUpdate && single.nextUpdate < now) {
+    throw new Error(`OCSP response has expired (nextUpdate ${single.nextUpdate.toISOString()})`);
+  }
+
+  return {
+    status: single.status,
+    revocationTime: single.revocationTime,
+    revocationReason: single.revocationReason,
+    thisUpdate: single.thisUpdate,
+    nextUpdate: single.nextUpdate,
+  };
+}
+
+/**
+ * Build a DER OCSPRequest for a certificate, ready to POST to its OCSP URL
+ * (Content-Type: application/ocsp-request). The CertID uses SHA-1, which all
+ * responders accept.
+ */
+export function buildOcspRequest(certPem, issuerPem) {
+  const asn1 = forge.asn1;
+  const certIdentity = _certificateIdentity(certificateFromPem(certPem));
+  const issuerIdentity = _certificateIdentity(certificateFromPem(issuerPem));
+  const certId = _certId(certIdentity, issuerIdentity, 'sha1');
+
+  // A positive INTEGER needs a leading zero byte when its high bit is set
+  let serial = forge.util.hexToBytes(certId.serialNumber.length % 2 ? '0' + certId.serialNumber : certId.serialNumber);
+  if (serial.charCodeAt(0) & 0x80) {
+    serial = '\x00' + serial;
+  }
+
+  const request = asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+    // tbsRequest
+    asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+      // requestList
+      asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+        // Request
+        asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+          // reqCert CertID
+          asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+            asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+              asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OID, false, asn1.oidToDer('1.3.14.3.2.26').getBytes()),
+              asn1.create(asn1.Class.UNIVERSAL, asn1.Type.NULL, false, ''),
+            ]),
+            asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OCTETSTRING, false, forge.util.hexToBytes(certId.issuerNameHash)),
+            asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OCTETSTRING, false, forge.util.hexToBytes(certId.issuerKeyHash)),
+            asn1.create(asn1.Class.UNIVERSAL, asn1.Type.INTEGER, false, serial),
+          ]),
+        ]),
+      ]),
+    ]),
+  ]);
+
+  return toBytes(asn1.toDer(request).getBytes());
+}
+
+function _isTime(node) {
+  return node && node.tagClass === forge.asn1.Class.UNIVERSAL &&
+    (node.type === forge.asn1.Type.UTCTIME || node.type === forge.asn1.Type.GENERALIZEDTIME);
+}
+
+/**
+ * Map an Extensions SEQUENCE to { [oid]: extnValue bytes }
+ */
+function _extensionValues(extensions) {
+  const values = {};
+  for (const extension of extensions ? extensions.value : []) {
+    // extnID, critical BOOLEAN DEFAULT FALSE, extnValue OCTET STRING
+    values[forge.asn1.derToOid(extension.value[0].value)] = extension.value[extension.value.length - 1].value;
+  }
+  return values;
+}
+
+/**
+ * The issuer named by a certificateIssuer entry extension (GeneralNames),
+ * preferring a directoryName
+ */
+function _certificateIssuerName(bytes) {
+  const names = fromDerRaw(bytes).value;
+  const directoryName = names.find(node => _isContext(node, 4));
+  return directoryName ? distinguishedNameFromAsn1(directoryName.value[0]) : names[0].value;
+}
+
+/**
+ * Parse a revoked entry. In an indirect CRL the certificateIssuer entry
+ * extension switches the issuer for that entry and all following ones.
+ */
+function _parseRevokedCertificate(entry, issuer) {
+  const [serialNumber, revocationDate, entryExtensions] = entry.value;
+  const extensions = _extensionValues(entryExtensions);
+  const reason = extensions['2.5.29.21'];
+  const code = reason ? fromDerRaw(reason).value.charCodeAt(0) : null;
+  const certificateIssuer = extensions['2.5.29.29'];
+
+  return {
+    serialNumber: unsignedHex(serialNumber.value),
+    revocationDate: parseTime(revocationDate),
+    reason: code === null ? null : REVOCATION_REASONS[code] || String(code),
+    certificateIssuer: certificateIssuer ? _certificateIssuerName(certificateIssuer) : issuer,
+  };
+}
+
+/**
+ * caIssuers URLs from a CRL's authorityInfoAccess extension (RFC 5280 5.2.7)
+ */
+function _crlIssuerCaUrls(aia) {
+  if (!aia) {
+    return [];
+  }
+  return fromDerRaw(aia).value
+    .filter(description => forge.asn1.derToOid(description.value[0].value) === '1.3.6.1.5.5.7.48.2')
+    .filter(description => _isContext(description.value[1], 6))
+    .map(description => description.value[1].value);
+}
+
+/**
+ * Parse a CRL (PEM "X509 CRL" or DER) into its issuer, update times, revoked
+ * entries (each with its effective certificateIssuer) and the URLs where the
+ * CRL issuer's certificate can be fetched
+ */
+export function parseCrl(crl, options = {}) {
+  checkInputSize(crl, options.maxInputBytes);
+  checkNotEmpty(crl);
+  let bytes = typeof crl === 'string' ? crl : forge.util.createBuffer(crl).getBytes();
+  if (bytes.includes('-----BEGIN X509 CRL-----')) {
+    bytes = forge.util.decode64(bytes.replace(/-----(BEGIN|END) X509 CRL-----/g, '').replace(/\s/g, ''));
+  }
+
+  const [tbsCertList, signatureAlgorithm] = fromDerRaw(bytes).value;
+  // Skip the optional version INTEGER
+  const fields = tbsCertList.value[0].type === forge.asn1.Type.INTEGER
+    ? tbsCertList.value.slice(1)
+    : tbsCertList.value;
+  const [, issuer, thisUpdate, ...rest] = fields;
+  const nextUpdate = _isTime(rest[0]) ? rest.shift() : null;
+  const revoked = rest.find(node => node.tagClass === forge.asn1.Class.UNIVERSAL && node.type === forge.asn1.Type.SEQUENCE);
+  const crlExtensions = rest.find(node => _isContext(node, 0));
+  const extensions = _extensionValues(crlExtensions ? crlExtensions.value[0] : null);
+
+  const signatureOid = forge.asn1.derToOid(signatureAlgorithm.value[0].value);
+  const crlNumber = extensions['2.5.29.20'];
+  const issuerDN = distinguishedNameFromAsn1(issuer);
+
+  const revokedCertificates = [];
+  let effectiveIssuer = issuerDN;
+  for (const entry of revoked ? revoked.value : []) {
+    const revokedCertificate = _parseRevokedCertificate(entry, effectiveIssuer);
+    effectiveIssuer = revokedCertificate.certificateIssuer;
+    revokedCertificates.push(revokedCertificate);
+  }
+
+  return {
+    issuer: issuerDN,
+    thisUpdate: parseTime(thisUpdate),
+    nextUpdate: nextUpdate ? parseTime(nextUpdate) : null,
+    signatureAlgorithm: SIGNATURE_ALGORITHMS[signatureOid] ? SIGNATURE_ALGORITHMS[signatureOid].name : signatureOid,
+    crlNumber: crlNumber ? unsignedHex(fromDerRaw(crlNumber).value) : null,
+    revokedCertificates,
+    crlIssuerCaUrls: _crlIssuerCaUrls(extensions['1.3.6.1.5.5.7.1.1']),
+  };
+}
@@ -0,0 +1,208 @@
+import forge from 'node-forge';
+import {
+  EC_CURVES,
+  SIGNATURE_ALGORITHMS,
+  certificateFromPem,
+  extractCertificateInfo,
+  tbsCertificateFields,
+  toBytes,
+  toPem,
+} from './certificateParser.js';
+
+/**
+ * Signature verification via WebCrypto (RSA PKCS#1 v1.5, ECDSA, Ed25519)
+ */
+
+const WEBCRYPTO_HASHES = {
+  sha1: 'SHA-1',
+  sha256: 'SHA-256',
+  sha384: 'SHA-384',
+  sha512: 'SHA-512',
+};
+
+const DIGEST_ALGORITHMS = {
+  '1.3.14.3.2.26': 'sha1',
+  '2.16.840.1.101.3.4.2.1': 'sha256',
+  '2.16.840.1.101.3.4.2.2': 'sha384',
+  '2.16.840.1.101.3.4.2.3': 'sha512',
+};
+
+// SignerInfo signature algorithms that only name the key type, resolved by digest
+const KEY_ONLY_SIGNATURE_OIDS = {
+  '1.2.840.113549.1.1.1': {
+    sha1: '1.2.840.113549.1.1.5',
+    sha256: '1.2.840.113549.1.1.11',
+    sha384: '1.2.840.113549.1.1.12',
+    sha512: '1.2.840.113549.1.1.13',
+  },
+  '1.2.840.10045.2.1': {
+    sha1: '1.2.840.10045.4.1',
+    sha256: '1.2.840.10045.4.3.2',
+    sha384: '1.2.840.10045.4.3.3',
+    sha512: '1.2.840.10045.4.3.4',
+  },
+};
+
+/**
+ * Convert a DER ECDSA-Sig-Value (r, s) to the fixed-width r || s WebCrypto expects
+ */
+function _ecdsaSignatureToRaw(signature, size) {
+  const sig = forge.asn1.fromDer(signature);
+  const pad = (integer) => integer.replace(/^\x00+/, '').padStart(size, '\x00');
+  return pad(sig.value[0].value) + pad(sig.value[1].value);
+}
+
+/**
+ * Verify a signature over `data` with the key in a DER SubjectPublicKeyInfo.
+ * All byte arguments are binary strings.
+ */
+export async function verifySignature(spkiDer, signatureOid, data, signature) {
+  const algorithm = SIGNATURE_ALGORITHMS[signatureOid];
+  const hash = algorithm && WEBCRYPTO_HASHES[algorithm.hash];
+  const spki = forge.asn1.fromDer(spkiDer, { decodeBitStrings: false });
+  const keyOid = forge.asn1.derToOid(spki.value[0].value[0].value);
+  const subtle = globalThis.crypto.subtle;
+
+  let importParams;
+  let verifyParams;
+  let signatureBytes = signature;
+
+  if (keyOid === '1.2.840.113549.1.1.1' && signatureOid.startsWith('1.2.840.113549.1.1.') && hash) {
+    importParams = { name: 'RSASSA-PKCS1-v1_5', hash };
+    verifyParams = { name: 'RSASSA-PKCS1-v1_5' };
+  } else if (keyOid === '1.2.840.10045.2.1' && signatureOid.startsWith('1.2.840.10045.4.') && hash) {
+    const curve = EC_CURVES[forge.asn1.derToOid(spki.value[0].value[1].value)];
+    if (!curve) {
+      throw new Error('Unsupported EC curve');
+    }
+    importParams = { name: 'ECDSA', namedCurve: curve.namedCurve };
+    verifyParams = { name: 'ECDSA', hash };
+    signatureBytes = _ecdsaSignatureToRaw(signature, curve.size);
+  } else if (keyOid === '1.3.101.112' && signatureOid === '1.3.101.112') {
+    importParams = { name: 'Ed25519' };
+    verifyParams = { name: 'Ed25519' };
+  } else {
+    throw new Error(`Unsupported signature algorithm: ${algorithm ? algorithm.name : signatureOid}`);
+  }
+
+  const key = await subtle.importKey('spki', toBytes(spkiDer), importParams, false, ['verify']);
+  return subtle.verify(verifyParams, key, toBytes(signatureBytes), toBytes(data));
+}
+
+/**
+ * Verify that `cert` was signed by the key of `issuerCert`.
+ * Both must carry their DER (see certificateFromPem).
+ */
+export async function verifyCertificateSignature(cert, issuerCert) {
+  const certAsn1 = forge.asn1.fromDer(cert.der, { decodeBitStrings: false });
+  const [tbs, signatureAlgorithm, signatureValue] = certAsn1.value;
+  const issuerSpki = tbsCertificateFields(issuerCert.der).subjectPublicKeyInfo;
+
+  return verifySignature(
+    forge.asn1.toDer(issuerSpki).getBytes(),
+    forge.asn1.derToOid(signatureAlgorithm.value[0].value),
+    forge.asn1.toDer(tbs).getBytes(),
+    // Skip the BIT STRING's unused-bits byte
+    signatureValue.value.substring(1)
+  );
+}
+
+/**
+ * Verify a certificate's signature against its own public key (roots, or any
+ * certificate worth a sanity check)
+ */
+export async function verifySelfSignature(pem) {
+  const cert = certificateFromPem(pem);
+  return verifyCertificateSignature(cert, cert);
+}
+
+function _binary(bytes) {
+  return typeof bytes === 'string' ? bytes : forge.util.createBuffer(bytes).getBytes();
+}
+
+/**
+ * Find the certificate a SignerInfo's sid names: IssuerAndSerialNumber, or a
+ * [0] subjectKeyIdentifier
+ */
+function _findSignerCertificate(sid, certificates) {
+  if (sid.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC) {
+    const keyId = forge.util.bytesToHex(sid.value);
+    return certificates.find(cert => extractCertificateInfo(cert).subjectKeyIdentifier === keyId);
+  }
+  const issuer = forge.asn1.toDer(sid.value[0]).getBytes();
+  const serial = sid.value[1].value;
+  return certificates.find(cert => {
+    const fields = tbsCertificateFields(cert.der);
+    return forge.asn1.toDer(fields.issuer).getBytes() === issuer && fields.serialNumber.value === serial;
+  });
+}
+
+/**
+ * Verify a CMS/PKCS#7 SignedData (DER bytes or binary string) over `data`,
+ * using the signer certificate embedded in it. `data` defaults to the
+ * encapsulated content for attached signatures. With signed attributes, the
+ * messageDigest must match the data and the signature covers the attributes.
+ * Only the first SignerInfo is checked; the signer certificate's own trust is not.
+ * Resolves to { valid, signer } where signer is the signer's certificate info.
+ */
+export async function verifyPkcs7Signature(p7Der, data = null) {
+  const contentInfo = forge.asn1.fromDer(_binary(p7Der), { decodeBitStrings: false });
+  if (forge.asn1.derToOid(contentInfo.value[0].value) !== '1.2.840.113549.1.7.2') {
+    throw new Error('Not a PKCS#7 SignedData');
+  }
+
+  // version, digestAlgorithms, encapContentInfo, [0] certificates, [1] crls, signerInfos
+  const signedData = contentInfo.value[1].value[0].value;
+  const encapContentInfo = signedData[2];
+  const certificates = signedData
+    .filter(node => node.tagClass === forge.asn1.Class.CONTEXT_SPECIFIC && node.type === 0)
+    .flatMap(node => node.value)
+    .map(node => certificateFromPem(toPem(forge.util.encode64(forge.asn1.toDer(node).getBytes()))));
+  const signerInfo = signedData[signedData.length - 1].value[0];
+  if (!signerInfo) {
+    throw new Error('SignedData has no signers');
+  }
+
+  const content = data !== null
+    ? _binary(data)
+    : encapContentInfo.value[1] && encapContentInfo.value[1].value[0].value;
+  if (typeof content !== 'string') {
+    throw new Error('No content to verify: the signature is detached');
+  }
+
+  // version, sid, digestAlgorithm, [0] signedAttrs, signatureAlgorithm, signature
+  const [, sid, digestAlgorithm, ...rest] = signerInfo.value;
+  const signedAttrs = rest[0].tagClass === forge.asn1.Class.CONTEXT_SPECIFIC ? rest.shift() : null;
+  const [signatureAlgorithm, signature] = rest;
+
+  const signerCert = _findSignerCertificate(sid, certificates);
+  if (!signerCert) {
+    throw new Error('Signer certificate not found in SignedData');
+  }
+  const signer = extractCertificateInfo(signerCert);
+
+  const digest = DIGEST_ALGORITHMS[forge.asn1.derToOid(digestAlgorithm.value[0].value)];
+  if (!digest) {
+    throw new Error('Unsupported digest algorithm');
+  }
+  const signatureOid = forge.asn1.derToOid(signatureAlgorithm.value[0].value);
+  const resolvedOid = KEY_ONLY_SIGNATURE_OIDS[signatureOid] ? KEY_ONLY_SIGNATURE_OIDS[signatureOid][digest] : signatureOid;
+
+  let signedBytes = content;
+  if (signedAttrs) {
+    const messageDigest = signedAttrs.value.find(attribute =>
+      forge.asn1.derToOid(attribute.value[0].value) === '1.2.840.113549.1.9.4'
+    );
+    const expected = forge.md[digest].create().update(content).digest().getBytes();
+    if (!messageDigest || messageDigest.value[1].value[0].value !== expected) {
+      return { valid: false, signer };
+    }
+    // The signature covers the attributes DER-encoded as a SET, not as [0]
+    const attributes = forge.asn1.create(forge.asn1.Class.UNIVERSAL, forge.asn1.Type.SET, true, signedAttrs.value);
+    signedBytes = forge.asn1.toDer(attributes).getBytes();
+  }
+
+  const spki = tbsCertificateFields(signerCert.der).subjectPublicKeyInfo;
+  const valid = await verifySignature(forge.asn1.toDer(spki).getBytes(), resolvedOid, signedBytes, signature.value);
+  return { valid, signer };
+}
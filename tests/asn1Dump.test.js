@@ -0,0 +1,43 @@
+/**
+ * Tests for raw ASN.1 dumps
+ */
+
+import { dumpAsn1 } from '../src/utils/asn1Dump.js';
+import { toSingleLineDer } from '../src/utils/certificateParser.js';
+import { TEST_LEAF } from './test-certificates.js';
+
+describe('ASN.1 Dump Tests', () => {
+  test('Dump the structure of a known certificate', () => {
+    const [certificate, ...rest] = dumpAsn1(TEST_LEAF);
+    const [tbs] = certificate.children;
+    const [version, serial, signature, issuer] = tbs.children;
+
+    expect(rest).toHaveLength(0);
+    expect(certificate).toMatchObject({ offset: 0, headerLength: 4, length: 500, type: 'SEQUENCE', constructed: true });
+    expect(certificate.children.map(node => node.type)).toEqual(['SEQUENCE', 'SEQUENCE', 'BIT STRING']);
+    expect(version).toMatchObject({ offset: 8, type: '[0]', tagClass: 'context', tagNumber: 0 });
+    expect(version.children[0]).toMatchObject({ type: 'INTEGER', hex: '02' });
+    expect(serial).toMatchObject({ offset: 13, type: 'INTEGER', hex: '3001' });
+    expect(signature.children[0]).toMatchObject({ type: 'OBJECT IDENTIFIER', value: '1.2.840.10045.4.3.2' });
+    expect(issuer.children[0].children[0].children.map(node => node.value)).toEqual(['2.5.4.6', 'US']);
+    expect(certificate.children[2].unusedBits).toBe(0);
+  });
+
+  test('Report the offset where truncated DER breaks', () => {
+    const der = Buffer.from(toSingleLineDer(TEST_LEAF), 'base64').subarray(0, 100);
+    const [certificate] = dumpAsn1(new Uint8Array(der));
+
+    const tbs = certificate.children[0];
+    const issuer = tbs.children[3];
+
+    expect(certificate.error).toBe('Length 500 at byte 0 exceeds the 96 bytes available');
+    expect(tbs.error).toBe('Length 411 at byte 4 exceeds the 92 bytes available');
+    expect(tbs.children.map(node => node.type)).toEqual(['[0]', 'INTEGER', 'SEQUENCE', 'SEQUENCE']);
+    expect(issuer.children[issuer.children.length - 1].error).toMatch(/at byte \d+ exceeds/);
+  });
+
+  test('Reject empty input and input over maxInputBytes', () => {
+    expect(() => dumpAsn1(TEST_LEAF, { maxInputBytes: 100 })).toThrow('Input too large');
+    expect(() => dumpAsn1('')).toThrow('Input is empty');
+  });
+});
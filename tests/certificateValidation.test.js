@@ -0,0 +1,481 @@
+/**
+ * Tests for certificate validation
+ */
+
+import {
+  explainTrust,
+  certMatchesHostname,
+  matchesPinSet,
+  validateChain,
+  checkRfc5280,
+  inRenewalWindow,
+  soonestExpiry,
+  isRenewalOf,
+  completeChain,
+  pruneBundle,
+  PruneReason,
+  chainHealth,
+  chainStats,
+  missingIssuers,
+  meetsBrowserProfile,
+  bundleReport,
+  duplicateSerials,
+  pkcs12ValidatedChain,
+  TrustIssue,
+  ConformanceIssue,
+} from '../src/utils/certificateValidation.js';
+import { certificateFromPem, extractCertificateInfo } from '../src/utils/certificateParser.js';
+import {
+  TEST_ROOT_CA,
+  TEST_INTERMEDIATE_CA,
+  TEST_LEAF,
+  TEST_SHA1_LEAF,
+  TEST_CONSTRAINED_CA,
+  TEST_CONSTRAINED_VIOLATION_LEAF,
+  TEST_SHA1_INTERMEDIATE_CA,
+  TEST_SHA256_LEAF_UNDER_SHA1,
+  TEST_NO_CONSTRAINTS_CA,
+  TEST_NONCONFORMANT_LEAF,
+  TEST_NO_SAN_SERVER_LEAF,
+  TEST_OUTLIVING_LEAF,
+  TEST_LEAF_RENEWED,
+  TEST_LEAF_REKEYED,
+  GOOGLE_ECDSA_CERT,
+  TEST_RETIRED_INTERMEDIATE_CA,
+  TEST_NON_CA_ISSUER,
+  TEST_LEAF_UNDER_NON_CA,
+  TEST_RSA1024_LEAF,
+  TEST_PKCS12_BASE64,
+  TEST_BROKEN_CHAIN_PKCS12_BASE64,
+} from './test-certificates.js';
+
+const NOW = new Date('2025-06-01T00:00:00Z');
+
+function reasonCodes(report) {
+  return report.reasons.map(reason => reason.code);
+}
+
+describe('Trust Diagnostics Tests', () => {
+  test('Complete chain to a trusted root has no reasons', () => {
+    const report = explainTrust(TEST_LEAF, [TEST_INTERMEDIATE_CA], [TEST_ROOT_CA], 'www.example.test', NOW);
+
+    expect(report.trusted).toBe(true);
+    expect(report.reasons).toEqual([]);
+    expect(report.chain.map(info => info.subjectCommonName)).toEqual([
+      'www.example.test',
+      'Test Intermediate CA',
+      'Test Root CA',
+    ]);
+  });
+
+  test('Expired leaf', () => {
+    const report = explainTrust(TEST_LEAF, [TEST_INTERMEDIATE_CA], [TEST_ROOT_CA], 'www.example.test',
+      new Date('2026-02-01T00:00:00Z'));
+
+    expect(report.trusted).toBe(false);
+    expect(reasonCodes(report)).toEqual([TrustIssue.EXPIRED]);
+    expect(report.reasons[0].subject).toBe('www.example.test');
+  });
+
+  test('Hostname mismatch', () => {
+    const report = explainTrust(TEST_LEAF, [TEST_INTERMEDIATE_CA], [TEST_ROOT_CA], 'mail.example.test', NOW);
+
+    expect(reasonCodes(report)).toEqual([TrustIssue.HOSTNAME_MISMATCH]);
+    expect(report.reasons[0].message).toContain('mail.example.test');
+  });
+
+  test('Incomplete chain when the intermediate is missing', () => {
+    const report = explainTrust(TEST_LEAF, [], [TEST_ROOT_CA], 'www.example.test', NOW);
+
+    expect(reasonCodes(report)).toEqual([TrustIssue.INCOMPLETE_CHAIN]);
+    expect(report.reasons[0].message).toContain('Test Intermediate CA');
+  });
+
+  test('Weak signature', () => {
+    const report = explainTrust(TEST_SHA1_LEAF, [TEST_INTERMEDIATE_CA], [TEST_ROOT_CA], 'legacy.example.test', NOW);
+
+    expect(reasonCodes(report)).toEqual([TrustIssue.WEAK_SIGNATURE]);
+    expect(report.reasons[0].subject).toBe('legacy.example.test');
+  });
+
+  test('Untrusted root', () => {
+    const report = explainTrust(TEST_LEAF, [TEST_INTERMEDIATE_CA, TEST_ROOT_CA], [], 'www.example.test', NOW);
+
+    expect(reasonCodes(report)).toEqual([TrustIssue.UNTRUSTED_ROOT]);
+    expect(report.reasons[0].subject).toBe('Test Root CA');
+  });
+
+  test('Name constraint violation', () => {
+    const report = explainTrust(TEST_CONSTRAINED_VIOLATION_LEAF, [TEST_CONSTRAINED_CA], [TEST_ROOT_CA],
+      'www.other.test', NOW);
+
+    expect(reasonCodes(report)).toEqual([TrustIssue.NAME_CONSTRAINT_VIOLATION]);
+    expect(report.reasons[0].subject).toBe('Constrained Intermediate CA');
+  });
+
+  test('Reasons are ordered by priority', () => {
+    const report = explainTrust(TEST_SHA1_LEAF, [TEST_INTERMEDIATE_CA, TEST_ROOT_CA], [], 'www.example.test',
+      new Date('2026-02-01T00:00:00Z'));
+
+    expect(reasonCodes(report)).toEqual([
+      TrustIssue.EXPIRED,
+      TrustIssue.HOSTNAME_MISMATCH,
+      TrustIssue.WEAK_SIGNATURE,
+      TrustIssue.UNTRUSTED_ROOT,
+    ]);
+  });
+
+  test('Hostname matching uses SANs', () => {
+    const info = extractCertificateInfo(certificateFromPem(TEST_LEAF));
+
+    expect(certMatchesHostname(info, 'www.example.test')).toBe(true);
+    expect(certMatchesHostname(info, 'example.test')).toBe(true);
+    expect(certMatchesHostname(info, 'other.example.test')).toBe(false);
+  });
+
+  test('Hostname matching ignores case and a trailing dot', () => {
+    const info = extractCertificateInfo(certificateFromPem(TEST_LEAF));
+
+    expect(certMatchesHostname(info, 'WWW.Example.TEST')).toBe(true);
+    expect(certMatchesHostname(info, 'www.example.test.')).toBe(true);
+    expect(certMatchesHostname(info, 'www.example.test..')).toBe(false);
+  });
+
+  test('Pin set matching uses the SPKI SHA-256 pin', () => {
+    // openssl x509 -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64
+    const leafPin = 'TdqwPx6lehXU6tr7lBh8YKBu01/rv/O5ulg2j3QvlRQ=';
+    const intermediatePin = 'Zlw5JPRJR5Kw4KCQ7Xompt3aTEC15+vOfC9jAv2tmJ8=';
+
+    expect(matchesPinSet(TEST_LEAF, [intermediatePin, leafPin])).toBe(true);
+    expect(matchesPinSet(TEST_LEAF, [`sha256/${leafPin}`])).toBe(true);
+    expect(matchesPinSet(TEST_LEAF, [intermediatePin])).toBe(false);
+    expect(matchesPinSet(TEST_LEAF, [])).toBe(false);
+  });
+
+  test('Wildcard matching ignores case and a trailing dot', () => {
+    const info = { dnsNames: ['*.Example.Test.'], ipAddresses: [] };
+
+    expect(certMatchesHostname(info, 'API.example.test')).toBe(true);
+    expect(certMatchesHostname(info, 'api.EXAMPLE.test.')).toBe(true);
+    expect(certMatchesHostname(info, 'a.b.example.test.')).toBe(false);
+  });
+});
+
+describe('Chain Validation Tests', () => {
+  test('Modern chain has no weak signatures', () => {
+    const result = validateChain([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], NOW);
+
+    expect(result.valid).toBe(true);
+    expect(result.errors).toEqual([]);
+    expect(result.chainHasWeakSignature).toBe(false);
+  });
+
+  test('Intermediate without CA=true is an invalid issuer', () => {
+    const result = validateChain([TEST_LEAF_UNDER_NON_CA, TEST_NON_CA_ISSUER, TEST_INTERMEDIATE_CA], NOW);
+
+    expect(result.valid).toBe(false);
+    expect(result.errors).toEqual([
+      'Certificate "Misplaced Leaf" is not a CA (basicConstraints CA=false) and cannot issue certificates',
+    ]);
+  });
+
+  test('SHA-1 intermediate under a SHA-256 leaf is flagged', () => {
+    const result = validateChain([TEST_SHA256_LEAF_UNDER_SHA1, TEST_SHA1_INTERMEDIATE_CA, TEST_ROOT_CA], NOW);
+
+    expect(result.valid).toBe(true);
+    expect(result.chainHasWeakSignature).toBe(true);
+    expect(result.weakSignatures).toEqual([
+      { index: 1, subject: 'Test SHA-1 Intermediate CA', signatureAlgorithm: 'ecdsa-with-SHA1' },
+    ]);
+  });
+
+  test('Chain warnings aggregate the per-certificate flags', () => {
+    const chain = [TEST_SHA256_LEAF_UNDER_SHA1, TEST_SHA1_INTERMEDIATE_CA, TEST_ROOT_CA];
+    const result = validateChain(chain, new Date('2025-12-27T00:00:00Z'));
+
+    expect(result.warnings).toEqual([
+      'Leaf "modern.example.test" expires in 5 days',
+      'Intermediate "Test SHA-1 Intermediate CA" uses a weak signature (ecdsa-with-SHA1)',
+      'Root "Test Root CA" is included unnecessarily; clients already have it',
+    ]);
+    expect(validateChain([TEST_LEAF, TEST_INTERMEDIATE_CA], NOW).warnings).toEqual([]);
+  });
+
+  test('Leaf expiring after its intermediate is reported', () => {
+    expect(validateChain([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], NOW).outlivesIssuer).toEqual([]);
+
+    const result = validateChain([TEST_OUTLIVING_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], NOW);
+
+    expect(result.outlivesIssuer).toEqual([{
+      index: 0,
+      subject: 'longlived.example.test',
+      validTo: new Date('2035-01-01T00:00:00Z'),
+      issuer: 'Test Intermediate CA',
+      issuerValidTo: new Date('2034-01-01T00:00:00Z'),
+    }]);
+  });
+});
+
+describe('Chain Completion Tests', () => {
+  test('Complete a chain with a root only present in the CA bundle', () => {
+    const chain = completeChain(
+      [TEST_INTERMEDIATE_CA, TEST_LEAF],
+      [GOOGLE_ECDSA_CERT, TEST_CONSTRAINED_CA, TEST_ROOT_CA]
+    );
+
+    expect(chain).toEqual([TEST_LEAF.trim(), TEST_INTERMEDIATE_CA.trim(), TEST_ROOT_CA.trim()]);
+  });
+
+  test('Pull a missing intermediate from the CA bundle', () => {
+    const chain = completeChain([TEST_LEAF], [TEST_ROOT_CA, TEST_INTERMEDIATE_CA]);
+
+    expect(chain).toEqual([TEST_LEAF.trim(), TEST_INTERMEDIATE_CA.trim(), TEST_ROOT_CA.trim()]);
+  });
+
+  test('Fail when the bundle lacks the issuer', () => {
+    expect(() => completeChain([TEST_LEAF], [TEST_ROOT_CA])).toThrow('issuer "Test Intermediate CA" not found');
+  });
+
+  test('Missing intermediate is listed with its DN and AIA URL', () => {
+    const issuers = missingIssuers([TEST_LEAF, TEST_ROOT_CA]);
+
+    expect(issuers).toHaveLength(1);
+    expect(issuers[0].issuerDN).toBe('CN=Test Intermediate CA, O=Cert Builder Test, C=US');
+    expect(issuers[0].issuedTo).toEqual(['CN=www.example.test']);
+    expect(missingIssuers([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA])).toEqual([]);
+
+    const [google] = missingIssuers([GOOGLE_ECDSA_CERT]);
+    expect(google.issuerDN).toBe('CN=WE2, O=Google Trust Services, C=US');
+    expect(google.caIssuerUrls).toEqual(['http://i.pki.goog/we2.crt']);
+  });
+});
+
+describe('RFC 5280 Conformance Tests', () => {
+  function issueCodes(pem) {
+    return checkRfc5280(pem).map(issue => issue.code);
+  }
+
+  test('Conformant certificates have no issues', () => {
+    expect(checkRfc5280(TEST_LEAF)).toEqual([]);
+    expect(checkRfc5280(TEST_INTERMEDIATE_CA)).toEqual([]);
+  });
+
+  test('CA without basicConstraints or SKI', () => {
+    expect(issueCodes(TEST_NO_CONSTRAINTS_CA)).toEqual([
+      ConformanceIssue.CA_MISSING_SKI,
+      ConformanceIssue.CA_MISSING_BASIC_CONSTRAINTS,
+    ]);
+  });
+
+  test('keyCertSign on a non-CA, empty SAN and negative serial', () => {
+    expect(issueCodes(TEST_NONCONFORMANT_LEAF)).toEqual([
+      ConformanceIssue.KEY_CERT_SIGN_WITHOUT_CA,
+      ConformanceIssue.EMPTY_SAN,
+      ConformanceIssue.SERIAL_NOT_POSITIVE,
+    ]);
+  });
+
+  test('Server leaf without SAN', () => {
+    const issues = checkRfc5280(TEST_NO_SAN_SERVER_LEAF);
+
+    expect(issues.map(issue => issue.code)).toEqual([ConformanceIssue.SERVER_LEAF_MISSING_SAN]);
+    expect(issues[0].message).toContain('nosan.example.test');
+  });
+});
+
+describe('Expiry Tests', () => {
+  // TEST_LEAF expires 2026-01-01
+  test('A certificate 20 days from expiry is inside a 30-day window', () => {
+    expect(inRenewalWindow(TEST_LEAF, 30, new Date('2025-12-12T00:00:00Z'))).toBe(true);
+  });
+
+  test('A certificate 60 days from expiry is outside a 30-day window', () => {
+    expect(inRenewalWindow(TEST_LEAF, 30, new Date('2025-11-02T00:00:00Z'))).toBe(false);
+  });
+
+  test('Find the soonest-expiring certificate in a set', () => {
+    expect(soonestExpiry([TEST_ROOT_CA, TEST_LEAF, TEST_INTERMEDIATE_CA])).toEqual({
+      index: 1,
+      notAfter: new Date('2026-01-01T00:00:00Z'),
+    });
+    expect(soonestExpiry([])).toBe(null);
+  });
+
+  test('Bundle report sorted by expiry lists the soonest-expiring certificate first', () => {
+    const pems = [TEST_ROOT_CA, TEST_LEAF, TEST_INTERMEDIATE_CA];
+
+    const byExpiry = bundleReport(pems, { sort: 'expiry', now: NOW });
+    expect(byExpiry.map(row => row.index)).toEqual([1, 2, 0]);
+    expect(byExpiry[0]).toMatchObject({
+      subject: 'CN=www.example.test',
+      notAfter: new Date('2026-01-01T00:00:00Z'),
+      daysUntilExpiry: 214,
+    });
+
+    expect(bundleReport(pems).map(row => row.index)).toEqual([0, 1, 2]);
+    expect(bundleReport(pems, { sort: 'subject' }).map(row => row.subject)).toEqual([
+      'CN=Test Intermediate CA, O=Cert Builder Test, C=US',
+      'CN=Test Root CA, O=Cert Builder Test, C=US',
+      'CN=www.example.test',
+    ]);
+    expect(() => bundleReport(pems, { sort: 'size' })).toThrow('Unknown report sort: size');
+  });
+});
+
+describe('Serial Number Reuse Tests', () => {
+  test('Two different certificates from one issuer sharing a serial are reported', () => {
+    // TEST_CONSTRAINED_CA and TEST_SHA1_INTERMEDIATE_CA were both issued by Test Root CA as serial 2002
+    const duplicates = duplicateSerials([TEST_CONSTRAINED_CA, TEST_LEAF, TEST_SHA1_INTERMEDIATE_CA, TEST_CONSTRAINED_CA]);
+
+    expect(duplicates).toEqual([{
+      issuer: 'CN=Test Root CA, O=Cert Builder Test, C=US',
+      serialNumber: '2002',
+      indices: [0, 2, 3],
+    }]);
+    expect(duplicateSerials([TEST_LEAF, TEST_LEAF, TEST_INTERMEDIATE_CA])).toEqual([]);
+  });
+});
+
+describe('Renewal Tests', () => {
+  test('Same-key renewal', () => {
+    expect(isRenewalOf(TEST_LEAF_RENEWED, TEST_LEAF)).toEqual({
+      isRenewal: true,
+      sameKey: true,
+      sameNames: true,
+      issuerChanged: false,
+    });
+  });
+
+  test('Re-keyed renewal with identical SANs', () => {
+    expect(isRenewalOf(TEST_LEAF_REKEYED, TEST_LEAF)).toEqual({
+      isRenewal: true,
+      sameKey: false,
+      sameNames: true,
+      issuerChanged: false,
+    });
+  });
+
+  test('Unrelated certificates', () => {
+    expect(isRenewalOf(GOOGLE_ECDSA_CERT, TEST_LEAF)).toEqual({
+      isRenewal: false,
+      sameKey: false,
+      sameNames: false,
+      issuerChanged: true,
+    });
+  });
+});
+
+describe('Bundle Pruning Tests', () => {
+  test('Drop expired and unused intermediates', () => {
+    const bundle = [TEST_LEAF, TEST_RETIRED_INTERMEDIATE_CA, TEST_INTERMEDIATE_CA, TEST_CONSTRAINED_CA, TEST_ROOT_CA];
+    const result = pruneBundle(bundle, NOW);
+
+    expect(result.kept).toEqual([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA]);
+    expect(result.removed.map(({ subject, reason }) => ({ subject, reason }))).toEqual([
+      { subject: 'CN=Test Retired Intermediate CA, O=Cert Builder Test, C=US', reason: PruneReason.EXPIRED },
+      { subject: extractCertificateInfo(certificateFromPem(TEST_CONSTRAINED_CA)).subjectDN, reason: PruneReason.UNUSED_INTERMEDIATE },
+    ]);
+  });
+
+  test('Keep intermediates when the bundle has no leaves', () => {
+    const bundle = [TEST_INTERMEDIATE_CA, TEST_CONSTRAINED_CA, TEST_ROOT_CA];
+
+    expect(pruneBundle(bundle, NOW)).toEqual({ kept: bundle, removed: [] });
+  });
+});
+
+describe('Chain Stats Tests', () => {
+  test('Chains with or without the root are minimal', () => {
+    expect(chainStats([TEST_LEAF, TEST_INTERMEDIATE_CA])).toEqual({ length: 2, hasRoot: false, isMinimal: true });
+    expect(chainStats([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA])).toEqual({ length: 3, hasRoot: true, isMinimal: true });
+  });
+
+  test('A duplicate or unrelated intermediate is not minimal', () => {
+    expect(chainStats([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_INTERMEDIATE_CA, TEST_ROOT_CA]))
+      .toEqual({ length: 4, hasRoot: true, isMinimal: false });
+    expect(chainStats([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_CONSTRAINED_CA]).isMinimal).toBe(false);
+  });
+});
+
+describe('Chain Health Tests', () => {
+  test('A complete, ordered, valid chain without the root gets an A', () => {
+    expect(chainHealth([TEST_LEAF, TEST_INTERMEDIATE_CA], 'www.example.test', NOW)).toEqual({
+      complete: true,
+      correctlyOrdered: true,
+      allValidDates: true,
+      hostnameCovered: true,
+      noWeakCrypto: true,
+      rootNotIncluded: true,
+      grade: 'A',
+    });
+    expect(chainHealth([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], 'www.example.test', NOW).grade).toBe('B');
+  });
+
+  test('A chain with several problems gets an F', () => {
+    const chain = [TEST_SHA1_INTERMEDIATE_CA, TEST_ROOT_CA, TEST_SHA256_LEAF_UNDER_SHA1];
+
+    expect(chainHealth(chain, 'www.example.test', new Date('2026-02-01T00:00:00Z'))).toEqual({
+      complete: true,
+      correctlyOrdered: false,
+      allValidDates: false,
+      hostnameCovered: false,
+      noWeakCrypto: false,
+      rootNotIncluded: false,
+      grade: 'F',
+    });
+    expect(chainHealth([TEST_LEAF], null, NOW)).toMatchObject({ complete: false, hostnameCovered: null, grade: 'F' });
+  });
+});
+
+describe('Browser Profile Tests', () => {
+  const profile = (chain, hostname = null, roots = []) => meetsBrowserProfile(chain, hostname, NOW, roots);
+
+  test('Modern chain to a root is accepted', () => {
+    expect(profile([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], 'www.example.test'))
+      .toEqual({ accepted: true, reasons: [] });
+    expect(profile([TEST_LEAF, TEST_INTERMEDIATE_CA], 'www.example.test', [TEST_ROOT_CA]).accepted).toBe(true);
+  });
+
+  test('Each browser rule is reported', () => {
+    const withRoot = (leaf) => profile([leaf, TEST_INTERMEDIATE_CA, TEST_ROOT_CA]).reasons;
+
+    expect(withRoot(TEST_NO_SAN_SERVER_LEAF)).toEqual(['Leaf "nosan.example.test" has no subjectAltName']);
+    expect(withRoot(TEST_OUTLIVING_LEAF)).toEqual([
+      'Leaf "longlived.example.test" is valid for 3652 days (maximum 398)',
+    ]);
+    expect(withRoot(TEST_SHA1_LEAF)).toEqual(['Certificate "legacy.example.test" is signed with ecdsa-with-SHA1']);
+    expect(withRoot(TEST_RSA1024_LEAF)).toEqual(['Certificate "weakkey.example.test" has a weak or unsupported 1024-bit RSA key']);
+    expect(profile([TEST_LEAF, TEST_INTERMEDIATE_CA], 'www.example.test').reasons).toEqual([
+      'Chain does not reach a root: issuer "CN=Test Root CA, O=Cert Builder Test, C=US" is missing',
+    ]);
+    expect(profile([TEST_LEAF, TEST_INTERMEDIATE_CA, TEST_ROOT_CA], 'www.other.test').reasons).toEqual([
+      'Leaf "www.example.test" does not cover www.other.test',
+    ]);
+  });
+});
+
+describe('PKCS#12 Chain Validation Tests', () => {
+  test('Orders and validates a complete chain', async () => {
+    const p12 = new Uint8Array(Buffer.from(TEST_PKCS12_BASE64, 'base64'));
+    const result = await pkcs12ValidatedChain(p12, 'test-password', NOW);
+
+    expect(result.valid).toBe(true);
+    expect(result.complete).toBe(true);
+    expect(result.chain).toHaveLength(3);
+    expect(result.chain.map(pem => extractCertificateInfo(certificateFromPem(pem)).subjectCommonName)).toEqual([
+      'www.example.test',
+      'Test Intermediate CA',
+      'Test Root CA',
+    ]);
+  });
+
+  test('Reports a missing issuer as a broken chain', async () => {
+    const p12 = new Uint8Array(Buffer.from(TEST_BROKEN_CHAIN_PKCS12_BASE64, 'base64'));
+    const result = await pkcs12ValidatedChain(p12, 'test-password', NOW);
+
+    expect(result.valid).toBe(false);
+    expect(result.complete).toBe(false);
+    expect(result.chain).toHaveLength(3);
+    expect(result.errors.some(error => error.includes('not issued by'))).toBe(true);
+  });
+});
@@ -0,0 +1,146 @@
+/**
+ * Tests for revocation checking
+ */
+
+import { parseOcspResponse, verifyOcsp, buildOcspRequest, parseCrl } from '../src/utils/revocation.js';
+import forge from 'node-forge';
+import {
+  TEST_INTERMEDIATE_CA,
+  TEST_LEAF,
+  TEST_DELTA_CRL_LEAF,
+  TEST_OCSP_GOOD_RESPONSE_BASE64,
+  TEST_OCSP_REVOKED_RESPONSE_BASE64,
+  TEST_CRL_WITH_AIA,
+  TEST_INDIRECT_CRL,
+} from './test-certificates.js';
+
+const GOOD_RESPONSE = Buffer.from(TEST_OCSP_GOOD_RESPONSE_BASE64, 'base64');
+const REVOKED_RESPONSE = Buffer.from(TEST_OCSP_REVOKED_RESPONSE_BASE64, 'base64');
+// Within both responses' thisUpdate (2025-06-01) .. nextUpdate (2025-06-08) window
+const NOW = new Date('2025-06-02T00:00:00Z');
+
+describe('OCSP Tests', () => {
+  test('Parse a good OCSP response', () => {
+    const response = parseOcspResponse(GOOD_RESPONSE);
+
+    expect(response.status).toBe('successful');
+    expect(response.responses).toHaveLength(1);
+    expect(response.responses[0].certId.serialNumber).toBe('3001');
+    expect(response.responses[0].certId.hashAlgorithm).toBe('sha1');
+    expect(response.responses[0].status).toBe('good');
+    expect(response.certificates).toEqual([]);
+  });
+
+  test('Verify a good OCSP response signed by the issuer', async () => {
+    const result = await verifyOcsp(GOOD_RESPONSE, TEST_LEAF, TEST_INTERMEDIATE_CA, NOW);
+
+    expect(result.status).toBe('good');
+    expect(result.revocationTime).toBeNull();
+    expect(result.thisUpdate.toISOString()).toBe('2025-06-01T00:00:00.000Z');
+    expect(result.nextUpdate.toISOString()).toBe('2025-06-08T00:00:00.000Z');
+  });
+
+  test('Verify a revoked OCSP response signed by a delegated responder', async () => {
+    const result = await verifyOcsp(REVOKED_RESPONSE, TEST_DELTA_CRL_LEAF, TEST_INTERMEDIATE_CA, NOW);
+
+    expect(result.status).toBe('revoked');
+    expect(result.revocationTime.toISOString()).toBe('2025-05-15T00:00:00.000Z');
+    expect(result.revocationReason).toBe('keyCompromise');
+  });
+
+  test('Reject a response for a different certificate', async () => {
+    await expect(verifyOcsp(REVOKED_RESPONSE, TEST_LEAF, TEST_INTERMEDIATE_CA, NOW))
+      .rejects.toThrow('does not cover this certificate');
+  });
+
+  test('Reject a response that is not yet valid or has expired', async () => {
+    await expect(verifyOcsp(GOOD_RESPONSE, TEST_LEAF, TEST_INTERMEDIATE_CA, new Date('2025-05-31T00:00:00Z')))
+      .rejects.toThrow('not yet valid');
+    await expect(verifyOcsp(GOOD_RESPONSE, TEST_LEAF, TEST_INTERMEDIATE_CA, new Date('2025-06-09T00:00:00Z')))
+      .rejects.toThrow('has expired');
+  });
+});
+
+describe('OCSP Request Tests', () => {
+  test('OCSP request CertID matches the responder', () => {
+    const request = buildOcspRequest(TEST_LEAF, TEST_INTERMEDIATE_CA);
+    const asn1 = forge.asn1.fromDer(forge.util.createBuffer(Buffer.from(request).toString('binary')));
+    const certId = asn1.value[0].value[0].value[0].value[0];
+
+    // The responder computed these for the same certificate and issuer
+    const expected = parseOcspResponse(GOOD_RESPONSE).responses[0].certId;
+    expect(forge.asn1.derToOid(certId.value[0].value[0].value)).toBe('1.3.14.3.2.26');
+    expect(forge.util.bytesToHex(certId.value[1].value)).toBe(expected.issuerNameHash);
+    expect(forge.util.bytesToHex(certId.value[2].value)).toBe(expected.issuerKeyHash);
+    expect(forge.util.bytesToHex(certId.value[3].value)).toBe('3001');
+  });
+});
+
+describe('CRL Tests', () => {
+  test('Parse a CRL with an authorityInfoAccess extension', () => {
+    const crl = parseCrl(TEST_CRL_WITH_AIA);
+
+    expect(crl.issuer).toBe('CN=Test Intermediate CA, O=Cert Builder Test, C=US');
+    expect(crl.thisUpdate).toEqual(new Date('2025-05-20T00:00:00Z'));
+    expect(crl.nextUpdate).toEqual(new Date('2025-06-20T00:00:00Z'));
+    expect(crl.crlNumber).toBe('07');
+    expect(crl.revokedCertificates).toEqual([
+      {
+        serialNumber: '3002',
+        revocationDate: new Date('2025-05-15T00:00:00Z'),
+        reason: 'keyCompromise',
+        certificateIssuer: 'CN=Test Intermediate CA, O=Cert Builder Test, C=US',
+      },
+    ]);
+    expect(crl.crlIssuerCaUrls).toEqual(['http://ca.example.test/intermediate.crt']);
+  });
+
+  test('Parse CRL times with fractional seconds and an explicit offset', () => {
+    const { asn1 } = forge;
+    const time = (value) => asn1.create(asn1.Class.UNIVERSAL, asn1.Type.GENERALIZEDTIME, false, value);
+    const algorithm = asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+      asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OID, false, asn1.oidToDer('1.2.840.10045.4.3.2').getBytes()),
+    ]);
+    const crl = asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+      asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+        algorithm,
+        asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+          asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SET, true, [
+            asn1.create(asn1.Class.UNIVERSAL, asn1.Type.SEQUENCE, true, [
+              asn1.create(asn1.Class.UNIVERSAL, asn1.Type.OID, false, asn1.oidToDer('2.5.4.3').getBytes()),
+              asn1.create(asn1.Class.UNIVERSAL, asn1.Type.UTF8, false, 'Fractional CA'),
+            ]),
+          ]),
+        ]),
+        time('20250520120000.25Z'),
+        time('20250620120000+0130'),
+      ]),
+      algorithm,
+      asn1.create(asn1.Class.UNIVERSAL, asn1.Type.BITSTRING, false, '\x00'),
+    ]);
+    const parsed = parseCrl(asn1.toDer(crl).getBytes());
+
+    expect(parsed.thisUpdate.toISOString()).toBe('2025-05-20T12:00:00.250Z');
+    expect(parsed.nextUpdate.toISOString()).toBe('2025-06-20T10:30:00.000Z');
+  });
+
+  test('Reject a CRL or OCSP response over maxInputBytes', () => {
+    expect(() => parseCrl(TEST_CRL_WITH_AIA, { maxInputBytes: 100 })).toThrow('Input too large');
+    expect(() => parseOcspResponse(new Uint8Array(200), { maxInputBytes: 100 })).toThrow('Input too large');
+  });
+
+  test('Reject an empty CRL or OCSP response', () => {
+    expect(() => parseCrl(' \n')).toThrow('Input is empty');
+    expect(() => parseOcspResponse(new Uint8Array(0))).toThrow('Input is empty');
+  });
+
+  test('Track the certificateIssuer across entries of an indirect CRL', () => {
+    const crl = parseCrl(TEST_INDIRECT_CRL);
+
+    expect(crl.revokedCertificates.map(entry => [entry.serialNumber, entry.certificateIssuer])).toEqual([
+      ['4001', 'CN=Test Intermediate CA, O=Cert Builder Test, C=US'],
+      ['4002', 'CN=Test Partner CA, O=Cert Builder Test, C=US'],
+      ['4003', 'CN=Test Partner CA, O=Cert Builder Test, C=US'],
+    ]);
+  });
+});
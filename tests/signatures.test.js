@@ -0,0 +1,50 @@
+/**
+ * Tests for signature verification
+ */
+
+import { verifyPkcs7Signature, verifySelfSignature } from '../src/utils/signatures.js';
+import { toPem, toSingleLineDer } from '../src/utils/certificateParser.js';
+import {
+  TEST_ROOT_CA,
+  TEST_LEAF,
+  TEST_SIGNED_CONTENT,
+  TEST_PKCS7_DETACHED_SIGNATURE_BASE64,
+  TEST_PKCS7_RSA_DETACHED_SIGNATURE_BASE64,
+} from './test-certificates.js';
+
+describe('Signature Verification Tests', () => {
+  test('A root verifies against its own key', async () => {
+    expect(await verifySelfSignature(TEST_ROOT_CA)).toBe(true);
+    expect(await verifySelfSignature(TEST_LEAF)).toBe(false);
+  });
+
+  test('A tampered root fails self-signature verification', async () => {
+    // Rename the issuer inside the signed TBSCertificate
+    const der = Buffer.from(toSingleLineDer(TEST_ROOT_CA), 'base64');
+    const offset = der.indexOf('Test Root CA');
+    der.write('Test Boot CA', offset);
+
+    expect(await verifySelfSignature(toPem(new Uint8Array(der)))).toBe(false);
+  });
+});
+
+describe('PKCS#7 Signature Tests', () => {
+  test('Verify a detached signature with signed attributes', async () => {
+    const p7 = new Uint8Array(Buffer.from(TEST_PKCS7_DETACHED_SIGNATURE_BASE64, 'base64'));
+    const result = await verifyPkcs7Signature(p7, TEST_SIGNED_CONTENT);
+
+    expect(result.valid).toBe(true);
+    expect(result.signer.subjectCommonName).toBe('www.example.test');
+    expect((await verifyPkcs7Signature(p7, 'Release artifact v1.2.4\n')).valid).toBe(false);
+  });
+
+  test('Verify an RSA detached signature without signed attributes', async () => {
+    const p7 = new Uint8Array(Buffer.from(TEST_PKCS7_RSA_DETACHED_SIGNATURE_BASE64, 'base64'));
+    const result = await verifyPkcs7Signature(p7, TEST_SIGNED_CONTENT);
+
+    expect(result.valid).toBe(true);
+    expect(result.signer.subjectCommonName).toBe('Test Code Signer');
+    expect((await verifyPkcs7Signature(p7, TEST_SIGNED_CONTENT.toUpperCase())).valid).toBe(false);
+    await expect(verifyPkcs7Signature(p7)).rejects.toThrow('signature is detached');
+  });
+});